@@ -1,15 +1,32 @@
-#[cfg(not(feature = "library"))]
+#[cfg(feature = "cosmwasm_1_1")]
+use crate::query::bank_supply_bundle_query;
+#[cfg(feature = "cosmwasm_1_3")]
+use crate::query::bank_denom_metadata_bundle_query;
+#[cfg(feature = "stargate")]
+use crate::query::{ibc_channels_bundle_query, stargate_bundle_query};
+#[cfg(feature = "staking")]
+use crate::query::{staking_delegations_bundle_query, staking_validators_bundle_query};
 use crate::{
     error::ContractError, 
     msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
     query::{
         cw721_bundle_query_ids, cw721_bundle_query_raw, cw721_bundle_query_smart,
+        cw721_bundle_verify_ownership, cw721_bundle_query_portfolio, cw721_bundle_query_trait_histogram,
+        cw2981_bundle_query_royalties,
         cw20_bundle_query_accounts, cw20_balances_bundle_query_raw, cw20_balances_bundle_query_smart,
-        generic_string_bundle_query_raw, generic_uint_bundle_query_raw
+        cw20_allowances_bundle_query_smart, cw20_allowances_bundle_query_raw,
+        cw20_bundle_query_all_allowances, cw20_bundle_query_all_spender_allowances,
+        cw20_bundle_query_metadata, cw20_bundle_query_marketing, cw20_bundle_query_portfolio, cw20_bundle_query_holder_snapshot,
+        cw20_bundle_query_holder_stats, cw20_bundle_query_supply_audit,
+        asset_balances_bundle_query, bank_balances_bundle_query, wasm_contract_info_bundle_query,
+        contract_bundle_query_classify,
+        generic_string_bundle_query_raw, generic_uint_bundle_query_raw, BundleResponse
     },
 };
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult
 };
 use cw2::set_contract_version;
 
@@ -40,48 +57,190 @@ pub fn execute(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    let data = query_bundle(deps, &env, msg)?;
+    to_json_binary(&BundleResponse::new(&env.block, data))
+}
+
+/// Dispatches `msg` to its bundle query, whose response is stamped with the block by [query]
+fn query_bundle(
+    deps: Deps,
+    env: &Env,
     msg: QueryMsg,
 ) -> StdResult<Binary> {
     match msg {
+        QueryMsg::WithMinHeight {
+            expected_min_height,
+            msg
+        } => {
+            if env.block.height < expected_min_height {
+                return Err(StdError::generic_err(format!(
+                    "Node is behind: height {} is below expected_min_height {}",
+                    env.block.height, expected_min_height
+                )));
+            }
+            query_bundle(deps, env, *msg)
+        },
         QueryMsg::Cw721BundleQuerySmart {
             token_ids,
             contract
-        } => to_binary(&cw721_bundle_query_smart(deps, token_ids, contract)?),
+        } => cw721_bundle_query_smart(deps, token_ids, contract),
         QueryMsg::Cw721BundleQueryRaw { 
             token_ids, 
-            contract 
-        } => to_binary(&cw721_bundle_query_raw(deps, token_ids, contract)?),
+            contract,
+            layout,
+            decode
+        } => cw721_bundle_query_raw(deps, token_ids, contract, layout, decode),
         QueryMsg::Cw721BundleQueryIds {
             loop_limit,
             max_limit,
             contract,
             start_after
-        } => to_binary(&cw721_bundle_query_ids(deps, loop_limit, max_limit, contract, start_after)?),
+        } => cw721_bundle_query_ids(deps, loop_limit, max_limit, contract, start_after),
+        QueryMsg::Cw721BundleVerifyOwnership {
+            owner,
+            tokens,
+            layout
+        } => cw721_bundle_verify_ownership(deps, owner, tokens, layout),
+        QueryMsg::Cw721BundleQueryPortfolio {
+            owner,
+            contracts,
+            loop_limit,
+            max_limit
+        } => cw721_bundle_query_portfolio(deps, owner, contracts, loop_limit, max_limit),
+        QueryMsg::Cw721BundleQueryTraitHistogram {
+            selection,
+            contract
+        } => cw721_bundle_query_trait_histogram(deps, selection, contract),
+        QueryMsg::Cw2981BundleQueryRoyalties {
+            token_ids,
+            sale_price,
+            contract
+        } => cw2981_bundle_query_royalties(deps, token_ids, sale_price, contract),
         QueryMsg::Cw20BundleQuerySmart { 
             accounts, 
             contract 
-        } => to_binary(&cw20_balances_bundle_query_smart(deps, accounts, contract)?),
+        } => cw20_balances_bundle_query_smart(deps, accounts, contract),
         QueryMsg::Cw20BundleQueryRaw { 
             accounts, 
             contract 
-        } => to_binary(&cw20_balances_bundle_query_raw(deps, accounts, contract)?),
+        } => cw20_balances_bundle_query_raw(deps, accounts, contract),
+        QueryMsg::Cw20AllowanceBundleQuerySmart {
+            allowances,
+            contract
+        } => cw20_allowances_bundle_query_smart(deps, &env.block, allowances, contract),
+        QueryMsg::Cw20AllowanceBundleQueryRaw {
+            allowances,
+            contract
+        } => cw20_allowances_bundle_query_raw(deps, &env.block, allowances, contract),
+        QueryMsg::Cw20BundleQueryAllAllowances {
+            owners,
+            loop_limit,
+            max_limit,
+            contract
+        } => cw20_bundle_query_all_allowances(deps, owners, loop_limit, max_limit, contract),
+        QueryMsg::Cw20BundleQueryAllSpenderAllowances {
+            spenders,
+            loop_limit,
+            max_limit,
+            contract
+        } => cw20_bundle_query_all_spender_allowances(deps, spenders, loop_limit, max_limit, contract),
+        QueryMsg::Cw20BundleQueryMetadata {
+            contracts
+        } => cw20_bundle_query_metadata(deps, contracts),
+        QueryMsg::Cw20BundleQueryMarketing {
+            contracts,
+            max_logo_size
+        } => cw20_bundle_query_marketing(deps, contracts, max_logo_size),
+        QueryMsg::Cw20PortfolioQuery {
+            account,
+            contracts,
+            mode
+        } => cw20_bundle_query_portfolio(deps, account, contracts, mode),
         QueryMsg::Cw20BundleQueryAccounts { 
             loop_limit, 
+            max_limit,
             contract, 
             start_after 
-        } => to_binary(&cw20_bundle_query_accounts(deps, loop_limit, contract, start_after)?),
+        } => cw20_bundle_query_accounts(deps, loop_limit, max_limit, contract, start_after),
+        QueryMsg::Cw20BundleQueryHolderSnapshot {
+            max_items,
+            max_limit,
+            contract,
+            start_after
+        } => cw20_bundle_query_holder_snapshot(deps, max_items, max_limit, contract, start_after),
+        QueryMsg::Cw20BundleQueryHolderStats {
+            loop_limit,
+            max_limit,
+            top_n,
+            bucket_bounds,
+            contract,
+            start_after
+        } => cw20_bundle_query_holder_stats(
+            deps, loop_limit, max_limit, top_n, bucket_bounds, contract, start_after
+        ),
+        QueryMsg::Cw20BundleQuerySupplyAudit {
+            loop_limit,
+            max_limit,
+            contract,
+            cursor
+        } => cw20_bundle_query_supply_audit(deps, loop_limit, max_limit, contract, cursor),
+        QueryMsg::AssetBalancesBundleQuery {
+            accounts,
+            assets,
+            mode
+        } => asset_balances_bundle_query(deps, accounts, assets, mode),
+        QueryMsg::BankBalancesBundleQuery {
+            addresses,
+            denoms,
+            all_balances
+        } => bank_balances_bundle_query(deps, addresses, denoms, all_balances),
+        #[cfg(feature = "cosmwasm_1_1")]
+        QueryMsg::BankSupplyBundleQuery {
+            denoms
+        } => bank_supply_bundle_query(deps, denoms),
+        #[cfg(feature = "cosmwasm_1_3")]
+        QueryMsg::BankDenomMetadataBundleQuery {
+            denoms
+        } => bank_denom_metadata_bundle_query(deps, denoms),
+        QueryMsg::WasmContractInfoBundleQuery {
+            contracts
+        } => wasm_contract_info_bundle_query(deps, contracts),
+        QueryMsg::ContractBundleQueryClassify {
+            contracts
+        } => contract_bundle_query_classify(deps, contracts),
+        #[cfg(feature = "staking")]
+        QueryMsg::StakingDelegationsBundleQuery {
+            delegators,
+            validator
+        } => staking_delegations_bundle_query(deps, delegators, validator),
+        #[cfg(feature = "staking")]
+        QueryMsg::StakingValidatorsBundleQuery {
+            validators
+        } => staking_validators_bundle_query(deps, validators),
+        #[cfg(feature = "stargate")]
+        QueryMsg::StargateBundleQuery {
+            queries
+        } => stargate_bundle_query(deps, queries),
+        #[cfg(feature = "stargate")]
+        QueryMsg::IbcChannelsBundleQuery {
+            list_channels,
+            list_port_id,
+            channels
+        } => ibc_channels_bundle_query(deps, list_channels, list_port_id, channels),
         QueryMsg::GenericStringBundleQueryRaw { 
             keys,
             namespace, 
             contract 
-        } => to_binary(&generic_string_bundle_query_raw(deps, keys, namespace, contract)?),
+        } => generic_string_bundle_query_raw(deps, keys, namespace, contract),
         QueryMsg::GenericUIntBundleQueryRaw { 
             keys, 
             keytype,
             namespace, 
             contract 
-        } => to_binary(&generic_uint_bundle_query_raw(deps, keys, keytype, namespace, contract)?)
+        } => generic_uint_bundle_query_raw(deps, keys, keytype, namespace, contract)
     }
 }
 
@@ -89,12 +248,1247 @@ pub fn query(
 #[allow(dead_code, unused)]
 mod tests {
     use super::*;
-    use cosmwasm_std::Binary;
+    use crate::{
+        msg::{Cw20AuditCursor, Cw721Layout},
+        query::{
+            Cw721Ownership, Cw721OwnershipRes, Cw721OwnershipVerification,
+            Cw721QueryResRaw, Cw721RawTokenInfo, Page, QueryResRaw, TraitHistogram,
+            Cw20AllowanceInfo, Cw20AllowanceRes, Cw20HolderStats, Cw20SupplyAudit,
+            BalanceRes, BankBalances, BundleReturn,
+        },
+    };
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, Binary, ContractResult, Empty, MemoryStorage, OwnedDeps, Uint128,
+        Storage, SystemError, SystemResult, WasmQuery,
+        coin, coins,
+        testing::{mock_dependencies, mock_dependencies_with_balances, mock_env, MockApi, MockQuerier},
+    };
+    use cw20::{AllAccountsResponse, AllowanceResponse, Cw20QueryMsg, Expiration, TokenInfoResponse};
+    use cw721_base::state::TokenInfo;
+    use std::collections::BTreeMap;
+
+    /// Mock dependencies that answer Raw Queries to any contract from `storage`
+    fn mock_deps_with_raw(storage: MemoryStorage) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |q| match q {
+            WasmQuery::Raw { key, .. } => SystemResult::Ok(ContractResult::Ok(
+                storage.get(key.as_slice()).unwrap_or_default().into()
+            )),
+            _ => SystemResult::Ok(ContractResult::Err("unsupported".to_string())),
+        });
+        deps
+    }
+
+    /// Mock dependencies of a cw20-base contract holding `balances`, with `total_supply`
+    fn mock_deps_with_cw20(balances: Vec<(&str, u128)>, total_supply: u128) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let mut storage = MemoryStorage::new();
+        let map = cw_storage_plus::Map::<&Addr, Uint128>::new("balance");
+        let mut accounts: Vec<String> = vec![];
+        for (account, balance) in balances {
+            map.save(&mut storage, &Addr::unchecked(account), &balance.into()).unwrap();
+            accounts.push(account.to_string());
+        }
+        accounts.sort();
+
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |q| match q {
+            WasmQuery::Raw { key, .. } => SystemResult::Ok(ContractResult::Ok(
+                storage.get(key.as_slice()).unwrap_or_default().into()
+            )),
+            WasmQuery::Smart { msg, .. } => SystemResult::Ok(match from_json(msg).unwrap() {
+                Cw20QueryMsg::AllAccounts { start_after, limit } => to_json_binary(&AllAccountsResponse {
+                    accounts: accounts.iter()
+                        .filter(|a| start_after.as_ref().is_none_or(|s| *a > s))
+                        .take(limit.unwrap_or(10) as usize)
+                        .cloned()
+                        .collect(),
+                }).into(),
+                Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                    name: "Token".to_string(),
+                    symbol: "TKN".to_string(),
+                    decimals: 6,
+                    total_supply: total_supply.into(),
+                }).into(),
+                _ => ContractResult::Err("unsupported".to_string()),
+            }),
+            _ => SystemResult::Ok(ContractResult::Err("unsupported".to_string())),
+        });
+        deps
+    }
+
+    /// Mock dependencies that answer Raw Queries from the storage of each contract,
+    /// and Smart Queries through `smart(contract, msg)`
+    fn mock_deps_with_contracts(
+        contracts: Vec<(&str, MemoryStorage)>,
+        smart: impl Fn(&str, &Binary) -> ContractResult<Binary> + 'static
+    ) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        let contracts: BTreeMap<String, MemoryStorage> = contracts.into_iter()
+            .map(|(contract, storage)| (contract.to_string(), storage))
+            .collect();
+
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |q| match q {
+            WasmQuery::Raw { contract_addr, key } => match contracts.get(contract_addr) {
+                None => SystemResult::Err(SystemError::NoSuchContract { addr: contract_addr.to_string() }),
+                Some(storage) => SystemResult::Ok(ContractResult::Ok(
+                    storage.get(key.as_slice()).unwrap_or_default().into()
+                )),
+            },
+            WasmQuery::Smart { contract_addr, msg } => SystemResult::Ok(smart(contract_addr, msg)),
+            _ => SystemResult::Ok(ContractResult::Err("unsupported".to_string())),
+        });
+        deps
+    }
+
+    /// Page of `items` after `start_after`, the same way cw-storage-plus ranges are paginated
+    fn page_of(items: &[&str], start_after: Option<String>, limit: Option<u32>) -> Vec<String> {
+        items.iter()
+            .filter(|item| start_after.as_deref().is_none_or(|s| **item > s))
+            .take(limit.unwrap_or(10) as usize)
+            .map(|item| item.to_string())
+            .collect()
+    }
+
+    /// Mock dependencies of a cw20 fork that lists `accounts`, but does not keep balances in the cw20-base `balance` map
+    fn mock_deps_with_cw20_fork(accounts: &'static [&'static str]) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+        mock_deps_with_contracts(vec![("token", MemoryStorage::new())], move |_, msg| match from_json(msg).unwrap() {
+            Cw20QueryMsg::AllAccounts { start_after, limit } => to_json_binary(&AllAccountsResponse {
+                accounts: page_of(accounts, start_after, limit),
+            }).into(),
+            _ => ContractResult::Err("unsupported".to_string()),
+        })
+    }
 
     #[test]
     fn null_byte() {
         let nullbyte = Binary::from(b"");
 
-        assert!(nullbyte.len() == 0);
+        assert!(nullbyte.is_empty());
+    }
+
+    #[test]
+    fn cw721_raw_layouts() {
+        let mut storage = MemoryStorage::new();
+        let info = TokenInfo::<Empty> {
+            owner: Addr::unchecked("owner"),
+            approvals: vec![],
+            token_uri: Some("ipfs://1".to_string()),
+            extension: Empty {},
+        };
+        cw_storage_plus::Map::<&str, TokenInfo<Empty>>::new("tokens").save(&mut storage, "1", &info).unwrap();
+        cw_storage_plus::Map::<&str, TokenInfo<Empty>>::new("nfts").save(&mut storage, "2", &info).unwrap();
+        let deps = mock_deps_with_raw(storage);
+
+        let query_raw = |layout: Option<Cw721Layout>, decode: Option<bool>| -> Binary {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleQueryRaw {
+                token_ids: vec!["1".to_string(), "2".to_string()],
+                contract: "collection".to_string(),
+                layout,
+                decode,
+            }).unwrap();
+            from_json::<BundleResponse>(&res).unwrap().data
+        };
+
+        // Values are returned as stored unless decoding is requested
+        let res: Vec<(String, QueryResRaw)> = from_json(query_raw(None, None)).unwrap();
+        assert_eq!(res[0].1, QueryResRaw::success(to_json_binary(&info).unwrap().to_base64()));
+        assert_eq!(res[1].1, QueryResRaw::error("Nonexistent key"));
+
+        let expected = Cw721RawTokenInfo {
+            owner: "owner".to_string(),
+            approvals: vec![],
+            token_uri: Some("ipfs://1".to_string()),
+            extension: Binary::from(b"{}"),
+        };
+
+        let res: Vec<(String, Cw721QueryResRaw)> = from_json(query_raw(None, Some(true))).unwrap();
+        assert_eq!(res[0].1, Cw721QueryResRaw::success(expected.clone()));
+        assert_eq!(res[1].1, Cw721QueryResRaw::error("Nonexistent key"));
+
+        // Every preset reads the `tokens` namespace
+        for layout in [Cw721Layout::Cw721BaseV15, Cw721Layout::Cw721BaseV18, Cw721Layout::Sg721Base] {
+            let res: Vec<(String, Cw721QueryResRaw)> = from_json(query_raw(Some(layout), Some(true))).unwrap();
+            assert_eq!(res[0].1, Cw721QueryResRaw::success(expected.clone()));
+        }
+
+        let layout = Cw721Layout::Custom {
+            tokens: "nfts".to_string(),
+        };
+        let res: Vec<(String, Cw721QueryResRaw)> = from_json(query_raw(Some(layout), Some(true))).unwrap();
+        assert_eq!(res[0].1, Cw721QueryResRaw::error("Nonexistent key"));
+        assert_eq!(res[1].1, Cw721QueryResRaw::success(expected));
+    }
+
+    #[test]
+    fn cw721_verify_ownership() {
+        let mut storage = MemoryStorage::new();
+        let tokens = cw_storage_plus::Map::<&str, TokenInfo<Empty>>::new("tokens");
+        for (id, owner) in [("1", "alice"), ("2", "bob")] {
+            tokens.save(&mut storage, id, &TokenInfo {
+                owner: Addr::unchecked(owner),
+                approvals: vec![],
+                token_uri: None,
+                extension: Empty {},
+            }).unwrap();
+        }
+        let deps = mock_deps_with_raw(storage);
+
+        let verify = |ids: Vec<&str>| -> Cw721OwnershipVerification {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleVerifyOwnership {
+                owner: "alice".to_string(),
+                tokens: ids.into_iter().map(|id| ("collection".to_string(), id.to_string())).collect(),
+                layout: None,
+            }).unwrap();
+            from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        let res = verify(vec!["1"]);
+        assert!(res.all_owned);
+
+        let res = verify(vec!["1", "2", "3"]);
+        assert!(!res.all_owned);
+        assert_eq!(res.tokens[1].2, Cw721OwnershipRes::success(Cw721Ownership::NotOwned));
+        assert_eq!(res.tokens[2].2, Cw721OwnershipRes::success(Cw721Ownership::Missing));
+    }
+
+    #[test]
+    fn trait_histogram_merge() {
+        let mut first = TraitHistogram {
+            token_count: 2,
+            traits: vec![
+                ("eyes".to_string(), "blue".to_string(), 1),
+                ("hat".to_string(), "cap".to_string(), 2),
+            ],
+            errors: vec![],
+            next_start_after: Some("2".to_string()),
+            exhausted: false,
+        };
+        first.merge(TraitHistogram {
+            token_count: 1,
+            traits: vec![
+                ("background".to_string(), "red".to_string(), 1),
+                ("hat".to_string(), "cap".to_string(), 1),
+            ],
+            errors: vec![("4".to_string(), "StdError::NotFound | token not found".to_string())],
+            next_start_after: Some("4".to_string()),
+            exhausted: true,
+        });
+
+        assert_eq!(first.token_count, 3);
+        assert_eq!(first.traits, vec![
+            ("background".to_string(), "red".to_string(), 1),
+            ("eyes".to_string(), "blue".to_string(), 1),
+            ("hat".to_string(), "cap".to_string(), 3),
+        ]);
+        assert_eq!(first.errors.len(), 1);
+        assert_eq!(first.next_start_after, Some("4".to_string()));
+        assert!(first.exhausted);
+    }
+
+    #[test]
+    fn trait_histogram_selections() {
+        use cw721::{Cw721QueryMsg, NftInfoResponse, TokensResponse};
+        use cw721_metadata_onchain::{Metadata, Trait};
+        use crate::msg::Cw721TokenSelection;
+
+        let deps = mock_deps_with_contracts(vec![], |_, msg| {
+            let traits = |traits: &[(&str, &str)]| Some(Metadata {
+                attributes: Some(traits.iter().map(|(trait_type, value)| Trait {
+                    display_type: None,
+                    trait_type: trait_type.to_string(),
+                    value: value.to_string(),
+                }).collect()),
+                ..Metadata::default()
+            });
+            match from_json(msg).unwrap() {
+                Cw721QueryMsg::AllTokens { start_after, limit } => to_json_binary(&TokensResponse {
+                    tokens: page_of(&["1", "2", "3", "4"], start_after, limit),
+                }).into(),
+                Cw721QueryMsg::NftInfo { token_id } => {
+                    let extension = match token_id.as_str() {
+                        "1" => traits(&[("hat", "cap"), ("eyes", "blue")]),
+                        "2" => traits(&[("hat", "cap")]),
+                        "3" => None,
+                        _ => return ContractResult::Err("token not found".to_string()),
+                    };
+                    to_json_binary(&NftInfoResponse { token_uri: None, extension }).into()
+                },
+                _ => ContractResult::Err("unsupported".to_string()),
+            }
+        });
+
+        let histogram = |selection: Cw721TokenSelection| -> TraitHistogram {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleQueryTraitHistogram {
+                selection,
+                contract: "collection".to_string(),
+            }).unwrap();
+            from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        let by_ids = histogram(Cw721TokenSelection::TokenIds(["1", "2", "3", "4"].map(String::from).to_vec()));
+        assert_eq!(by_ids.token_count, 3);
+        assert_eq!(by_ids.traits, vec![
+            ("eyes".to_string(), "blue".to_string(), 1),
+            ("hat".to_string(), "cap".to_string(), 2),
+        ]);
+        assert_eq!(by_ids.errors.len(), 1);
+        assert_eq!(by_ids.errors[0].0, "4");
+        assert!(by_ids.exhausted);
+
+        let mut first = histogram(Cw721TokenSelection::AllTokens {
+            loop_limit: 1,
+            max_limit: Some(2),
+            start_after: None,
+        });
+        assert_eq!(first.token_count, 2);
+        assert_eq!(first.next_start_after, Some("2".to_string()));
+        assert!(!first.exhausted);
+
+        // The last page is full, so an empty page is needed to know every token was read
+        let second = histogram(Cw721TokenSelection::AllTokens {
+            loop_limit: 2,
+            max_limit: Some(2),
+            start_after: first.next_start_after.clone(),
+        });
+        assert_eq!(second.token_count, 1);
+        assert_eq!(second.next_start_after, Some("4".to_string()));
+        assert!(second.exhausted);
+
+        first.merge(second);
+        assert_eq!(first.token_count, by_ids.token_count);
+        assert_eq!(first.traits, by_ids.traits);
+        assert_eq!(first.errors, by_ids.errors);
+        assert!(first.exhausted);
+    }
+
+    #[test]
+    fn cw2981_royalties() {
+        use cw2981_royalties::msg::{Cw2981QueryMsg, RoyaltiesInfoResponse};
+        use crate::query::Cw2981QueryResSmart;
+
+        let deps = mock_deps_with_contracts(vec![], |contract, msg| {
+            // cw2981-royalties only answers RoyaltyInfo wrapped in `Cw721QueryMsg::Extension`
+            match (contract, from_json::<cw2981_royalties::QueryMsg>(msg)) {
+                ("royalties", Ok(cw2981_royalties::QueryMsg::Extension {
+                    msg: Cw2981QueryMsg::RoyaltyInfo { token_id, sale_price }
+                })) if token_id == "1" => to_json_binary(&RoyaltiesInfoResponse {
+                    address: "artist".to_string(),
+                    royalty_amount: sale_price.multiply_ratio(5u128, 100u128),
+                }).into(),
+                ("royalties", _) => ContractResult::Err("token not found".to_string()),
+                _ => ContractResult::Err("unknown variant `extension`".to_string()),
+            }
+        });
+
+        let royalties = |contract: &str| -> Vec<(String, Cw2981QueryResSmart)> {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw2981BundleQueryRoyalties {
+                token_ids: vec!["1".to_string(), "2".to_string()],
+                sale_price: Uint128::new(1000),
+                contract: contract.to_string(),
+            }).unwrap();
+            from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        let res = royalties("royalties");
+        assert_eq!(res[0].1, Cw2981QueryResSmart::success(RoyaltiesInfoResponse {
+            address: "artist".to_string(),
+            royalty_amount: Uint128::new(50),
+        }));
+        assert!(matches!(res[1].1, BundleReturn::Error(_)));
+
+        // Collections without cw2981 return an error per token instead of failing the call
+        let res = royalties("collection");
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().all(|(_, royalty)| matches!(royalty, BundleReturn::Error(_))));
+    }
+
+    #[test]
+    fn cw20_allowances_modes() {
+        let mut storage = MemoryStorage::new();
+        let env = mock_env();
+        let allowance = AllowanceResponse {
+            allowance: 100u128.into(),
+            expires: Expiration::AtHeight(env.block.height),
+        };
+        let allowances = cw_storage_plus::Map::<(&Addr, &Addr), AllowanceResponse>::new("allowance");
+        allowances.save(&mut storage, (&Addr::unchecked("owner"), &Addr::unchecked("spender")), &allowance).unwrap();
+        let deps = mock_deps_with_contracts(vec![("token", storage)], move |contract, msg| match (contract, from_json(msg).unwrap()) {
+            ("token", Cw20QueryMsg::Allowance { owner, spender }) => match (owner.as_str(), spender.as_str()) {
+                ("owner", "spender") => to_json_binary(&allowance).into(),
+                _ => to_json_binary(&AllowanceResponse::default()).into(),
+            },
+            _ => ContractResult::Err("unsupported".to_string()),
+        });
+
+        let pairs = vec![
+            ("owner".to_string(), "spender".to_string()),
+            ("spender".to_string(), "owner".to_string()),
+        ];
+        let expected = vec![
+            Cw20AllowanceRes::success(Cw20AllowanceInfo {
+                allowance: 100u128.into(),
+                expires: Expiration::AtHeight(env.block.height),
+                expired: true,
+            }),
+            Cw20AllowanceRes::success(Cw20AllowanceInfo {
+                allowance: Uint128::zero(),
+                expires: Expiration::Never {},
+                expired: false,
+            }),
+        ];
+
+        let query_allowances = |msg: QueryMsg| -> Vec<Cw20AllowanceRes> {
+            let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let res: Vec<(String, String, Cw20AllowanceRes)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+            res.into_iter().map(|(_, _, allowance)| allowance).collect()
+        };
+
+        // An allowance that was never set is zero in both modes
+        assert_eq!(query_allowances(QueryMsg::Cw20AllowanceBundleQueryRaw {
+            allowances: pairs.clone(),
+            contract: "token".to_string(),
+        }), expected);
+        assert_eq!(query_allowances(QueryMsg::Cw20AllowanceBundleQuerySmart {
+            allowances: pairs.clone(),
+            contract: "token".to_string(),
+        }), expected);
+
+        let res = query_allowances(QueryMsg::Cw20AllowanceBundleQuerySmart {
+            allowances: pairs,
+            contract: "collection".to_string(),
+        });
+        assert!(res.iter().all(|allowance| matches!(allowance, BundleReturn::Error(_))));
+    }
+
+    #[test]
+    fn page_limit_validated_up_front() {
+        let deps = mock_dependencies();
+
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryAccounts {
+            loop_limit: 1,
+            max_limit: Some(31),
+            contract: "token".to_string(),
+            start_after: None,
+        }).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Page limit cannot be > 30");
+
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleQueryIds {
+            loop_limit: 1,
+            max_limit: Some(0),
+            contract: "collection".to_string(),
+            start_after: None,
+        }).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Page limit cannot be 0");
+    }
+
+    #[test]
+    fn enumeration_pages() {
+        let deps = mock_deps_with_cw20(vec![("a", 1), ("b", 2), ("c", 3), ("d", 4)], 10);
+
+        let accounts = |loop_limit: u32, start_after: Option<String>| -> Page<String> {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryAccounts {
+                loop_limit,
+                max_limit: Some(2),
+                contract: "token".to_string(),
+                start_after,
+            }).unwrap();
+            from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        // The last page is full, so the accounts are not known to be exhausted yet
+        let page = accounts(2, None);
+        assert_eq!(page, Page {
+            items: ["a", "b", "c", "d"].map(String::from).to_vec(),
+            next_start_after: Some("d".to_string()),
+            exhausted: false,
+        });
+        assert_eq!(accounts(1, page.next_start_after), Page {
+            items: vec![],
+            next_start_after: Some("d".to_string()),
+            exhausted: true,
+        });
+
+        let deps = mock_deps_with_contracts(vec![], |_, msg| match from_json(msg).unwrap() {
+            cw721::Cw721QueryMsg::AllTokens { start_after, limit } => to_json_binary(&cw721::TokensResponse {
+                tokens: page_of(&["1", "2", "3"], start_after, limit),
+            }).into(),
+            _ => ContractResult::Err("unsupported".to_string()),
+        });
+
+        let token_ids = |loop_limit: u32, start_after: Option<String>| -> Page<String> {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleQueryIds {
+                loop_limit,
+                max_limit: Some(2),
+                contract: "collection".to_string(),
+                start_after,
+            }).unwrap();
+            from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        let page = token_ids(1, None);
+        assert_eq!(page, Page {
+            items: ["1", "2"].map(String::from).to_vec(),
+            next_start_after: Some("2".to_string()),
+            exhausted: false,
+        });
+        assert_eq!(token_ids(5, page.next_start_after), Page {
+            items: vec!["3".to_string()],
+            next_start_after: Some("3".to_string()),
+            exhausted: true,
+        });
+    }
+
+    #[test]
+    fn holder_snapshot_clips_last_page() {
+        let deps = mock_deps_with_cw20(vec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)], 15);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryHolderSnapshot {
+            max_items: 3,
+            max_limit: Some(2),
+            contract: "token".to_string(),
+            start_after: None,
+        }).unwrap();
+        let res: Page<(String, BalanceRes)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        // The second page only requests the one account left in the budget
+        assert_eq!(res.items, vec![
+            ("a".to_string(), BalanceRes::success(Uint128::new(1))),
+            ("b".to_string(), BalanceRes::success(Uint128::new(2))),
+            ("c".to_string(), BalanceRes::success(Uint128::new(3))),
+        ]);
+        assert_eq!(res.next_start_after, Some("c".to_string()));
+        assert!(!res.exhausted);
+    }
+
+    #[test]
+    fn holder_snapshot_missing_balances() {
+        let deps = mock_deps_with_cw20_fork(&["alice", "bob"]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryHolderSnapshot {
+            max_items: 5,
+            max_limit: None,
+            contract: "token".to_string(),
+            start_after: None,
+        }).unwrap();
+        let res: Page<(String, BalanceRes)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res.items, vec![
+            ("alice".to_string(), BalanceRes::error("Nonexistent key")),
+            ("bob".to_string(), BalanceRes::error("Nonexistent key")),
+        ]);
+        assert!(res.exhausted);
+    }
+
+    #[test]
+    fn holder_stats_merge() {
+        let stats = |holders: Vec<(&str, u128)>, buckets: Vec<(u128, u64)>| Cw20HolderStats {
+            holder_count: holders.len() as u64,
+            non_zero_count: holders.iter().filter(|h| h.1 > 0).count() as u64,
+            total_balance: holders.iter().map(|h| h.1).sum::<u128>().into(),
+            top_n: 2,
+            top_holders: holders.into_iter().map(|(a, b)| (a.to_string(), b.into())).collect(),
+            buckets: buckets.into_iter().map(|(bound, count)| (bound.into(), count)).collect(),
+            errors: vec![],
+            next_start_after: None,
+            exhausted: false,
+        };
+
+        let mut first = stats(vec![("bob", 500), ("alice", 0)], vec![(0, 1), (100, 1)]);
+        first.merge(stats(vec![("carol", 700), ("dave", 50)], vec![(0, 1), (100, 1)])).unwrap();
+
+        assert_eq!(first.holder_count, 4);
+        assert_eq!(first.non_zero_count, 3);
+        assert_eq!(first.total_balance, Uint128::new(1250));
+        assert_eq!(first.top_holders, vec![
+            ("carol".to_string(), 700u128.into()),
+            ("bob".to_string(), 500u128.into()),
+        ]);
+        assert_eq!(first.buckets, vec![(0u128.into(), 2), (100u128.into(), 2)]);
+
+        assert!(first.merge(stats(vec![], vec![(0, 0)])).is_err());
+    }
+
+    #[test]
+    fn holder_stats_missing_balances() {
+        let deps = mock_deps_with_cw20_fork(&["alice", "bob"]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryHolderStats {
+            loop_limit: 1,
+            max_limit: None,
+            top_n: 2,
+            bucket_bounds: vec![],
+            contract: "token".to_string(),
+            start_after: None,
+        }).unwrap();
+        let res: Cw20HolderStats = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res.holder_count, 0);
+        assert_eq!(res.top_holders, vec![]);
+        assert_eq!(res.errors, vec![
+            ("alice".to_string(), "Nonexistent key".to_string()),
+            ("bob".to_string(), "Nonexistent key".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn cw20_supply_audit() {
+        let deps = mock_deps_with_cw20(vec![("alice", 100), ("bob", 200), ("carol", 300)], 600);
+
+        let audit = |cursor: Option<Cw20AuditCursor>| -> Cw20SupplyAudit {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQuerySupplyAudit {
+                loop_limit: 1,
+                max_limit: Some(2),
+                contract: "token".to_string(),
+                cursor,
+            }).unwrap();
+            from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        let first = audit(None);
+        assert_eq!(first.accounts_read, 2);
+        assert_eq!(first.cursor.running_total, Uint128::new(300));
+        assert_eq!(first.reconciled, None);
+
+        let second = audit(Some(first.cursor));
+        assert_eq!(second.accounts_read, 1);
+        assert!(second.exhausted);
+        assert_eq!(second.reconciled, Some(true));
+    }
+
+    #[test]
+    fn cw20_supply_audit_fails_on_missing_balances() {
+        let deps = mock_deps_with_cw20_fork(&["alice", "bob"]);
+
+        // A layout mismatch must not be reported as an unreconciled supply
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQuerySupplyAudit {
+            loop_limit: 1,
+            max_limit: None,
+            contract: "token".to_string(),
+            cursor: None,
+        }).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Balance of alice: Nonexistent key");
+    }
+
+    #[test]
+    fn bank_balances() {
+        let deps = mock_dependencies_with_balances(&[("alice", &coins(100, "ujuno"))]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::BankBalancesBundleQuery {
+            addresses: vec!["alice".to_string(), "bob".to_string()],
+            denoms: vec!["ujuno".to_string(), "uatom".to_string()],
+            all_balances: true,
+        }).unwrap();
+        let res: Vec<(String, BankBalances)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1.balances, vec![
+            ("ujuno".to_string(), BalanceRes::success(Uint128::new(100))),
+            ("uatom".to_string(), BalanceRes::success(Uint128::zero())),
+        ]);
+        assert_eq!(res[0].1.all_balances, Some(BundleReturn::success(coins(100, "ujuno"))));
+        assert_eq!(res[1].1.all_balances, Some(BundleReturn::success(vec![])));
+    }
+
+    #[cfg(feature = "cosmwasm_1_1")]
+    #[test]
+    fn bank_supply() {
+        let deps = mock_dependencies_with_balances(&[
+            ("alice", &coins(100, "ujuno")),
+            ("bob", &coins(50, "ujuno")),
+        ]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::BankSupplyBundleQuery {
+            denoms: vec!["ujuno".to_string(), "uatom".to_string()],
+        }).unwrap();
+        let res: Vec<(String, BalanceRes)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res, vec![
+            ("ujuno".to_string(), BalanceRes::success(Uint128::new(150))),
+            ("uatom".to_string(), BalanceRes::success(Uint128::zero())),
+        ]);
+    }
+
+    #[cfg(feature = "staking")]
+    #[test]
+    fn staking_delegations() {
+        use cosmwasm_std::{Decimal, FullDelegation, Validator};
+        use crate::query::StakingDelegations;
+
+        let mut deps = mock_dependencies();
+        let validator = Validator {
+            address: "val1".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(20),
+            max_change_rate: Decimal::percent(1),
+        };
+        let delegation = FullDelegation {
+            delegator: Addr::unchecked("alice"),
+            validator: "val1".to_string(),
+            amount: coin(100, "ujuno"),
+            can_redelegate: coin(40, "ujuno"),
+            accumulated_rewards: coins(7, "ujuno"),
+        };
+        deps.querier.update_staking("ujuno", &[validator], std::slice::from_ref(&delegation));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StakingDelegationsBundleQuery {
+            delegators: vec!["alice".to_string(), "bob".to_string()],
+            validator: Some("val1".to_string()),
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<StakingDelegations>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1, BundleReturn::success(StakingDelegations::Validator(Some(delegation.clone()))));
+        assert_eq!(res[1].1, BundleReturn::success(StakingDelegations::Validator(None)));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StakingDelegationsBundleQuery {
+            delegators: vec!["alice".to_string()],
+            validator: None,
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<StakingDelegations>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1, BundleReturn::success(StakingDelegations::All(vec![delegation.into()])));
+    }
+
+    #[cfg(feature = "staking")]
+    #[test]
+    fn staking_validators() {
+        use cosmwasm_std::{Decimal, Validator};
+        use crate::query::StakingValidators;
+
+        let mut deps = mock_dependencies();
+        let validator = Validator {
+            address: "val1".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(20),
+            max_change_rate: Decimal::percent(1),
+        };
+        deps.querier.update_staking("ujuno", std::slice::from_ref(&validator), &[]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StakingValidatorsBundleQuery {
+            validators: vec!["val1".to_string(), "val2".to_string()],
+        }).unwrap();
+        let res: StakingValidators = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res.bonded_denom, BundleReturn::success("ujuno".to_string()));
+        assert_eq!(res.all_validators, BundleReturn::success(vec![validator.clone()]));
+        assert_eq!(res.validators, vec![
+            ("val1".to_string(), BundleReturn::success(Some(validator))),
+            ("val2".to_string(), BundleReturn::success(None)),
+        ]);
+    }
+
+    #[test]
+    fn wasm_contract_info() {
+        use crate::query::WasmContractInfo;
+
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|q| match q {
+            WasmQuery::ContractInfo { contract_addr } if contract_addr != "missing" => SystemResult::Ok(ContractResult::Ok(
+                Binary::from(br#"{"code_id":7,"creator":"creator","admin":null,"pinned":true,"ibc_port":null}"#.as_slice())
+            )),
+            #[cfg(feature = "cosmwasm_1_2")]
+            WasmQuery::CodeInfo { code_id } => SystemResult::Ok(ContractResult::Ok(
+                Binary::from(format!(r#"{{"code_id":{code_id},"creator":"creator","checksum":"00ff"}}"#).as_bytes())
+            )),
+            _ => SystemResult::Err(SystemError::NoSuchContract { addr: "missing".to_string() }),
+        });
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::WasmContractInfoBundleQuery {
+            contracts: vec!["a".to_string(), "missing".to_string(), "b".to_string()],
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<WasmContractInfo>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        let BundleReturn::Success(info) = &res[0].1 else { panic!("expected contract info") };
+        assert_eq!(info.contract_info.code_id, 7);
+        assert!(info.contract_info.pinned);
+        assert!(matches!(res[1].1, BundleReturn::Error(_)));
+        assert_eq!(res[0].1, res[2].1);
+
+        #[cfg(feature = "cosmwasm_1_2")]
+        {
+            let BundleReturn::Success(code_info) = &info.code_info else { panic!("expected code info") };
+            assert_eq!(code_info.checksum.to_hex(), "00ff");
+        }
+    }
+
+    #[test]
+    fn contract_classification() {
+        use cw2::ContractVersion;
+        use crate::query::{ContractClassification, ContractStandard};
+
+        let mut nft = MemoryStorage::new();
+        cw_storage_plus::Item::<u64>::new("num_tokens").save(&mut nft, &5).unwrap();
+        let mut token = MemoryStorage::new();
+        cw2::set_contract_version(&mut token, "crates.io:cw20-base", "1.0.1").unwrap();
+        cw_storage_plus::Item::<u64>::new("token_info").save(&mut token, &0).unwrap();
+        let mut group = MemoryStorage::new();
+        cw2::set_contract_version(&mut group, "crates.io:cw4-group", "1.1.2").unwrap();
+        cw_storage_plus::Item::<u64>::new("total").save(&mut group, &10).unwrap();
+        let mut dao = MemoryStorage::new();
+        cw_storage_plus::Item::<String>::new("voting_module").save(&mut dao, &"voting".to_string()).unwrap();
+        // A wrapper whose name contains a known standard but whose storage matches none of the probes
+        let mut staked = MemoryStorage::new();
+        cw2::set_contract_version(&mut staked, "crates.io:dao-voting-cw721-staked", "2.4.0").unwrap();
+        let deps = mock_deps_with_contracts(vec![
+            ("nft", nft),
+            ("token", token),
+            ("group", group),
+            ("dao", dao),
+            ("staked", staked),
+            ("other", MemoryStorage::new()),
+        ], |_, _| ContractResult::Err("unsupported".to_string()));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ContractBundleQueryClassify {
+            contracts: ["nft", "token", "group", "dao", "staked", "other", "missing"].map(String::from).to_vec(),
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<ContractClassification>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Cw721,
+            contract_version: None,
+            probes: vec!["num_tokens".to_string()],
+        }));
+        assert_eq!(res[1].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Cw20,
+            contract_version: Some(ContractVersion {
+                contract: "crates.io:cw20-base".to_string(),
+                version: "1.0.1".to_string(),
+            }),
+            probes: vec!["token_info".to_string()],
+        }));
+        assert_eq!(res[2].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Cw4Group,
+            contract_version: Some(ContractVersion {
+                contract: "crates.io:cw4-group".to_string(),
+                version: "1.1.2".to_string(),
+            }),
+            probes: vec!["total".to_string()],
+        }));
+        assert_eq!(res[3].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Dao,
+            contract_version: None,
+            probes: vec!["voting_module".to_string()],
+        }));
+        assert_eq!(res[4].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Unknown,
+            contract_version: Some(ContractVersion {
+                contract: "crates.io:dao-voting-cw721-staked".to_string(),
+                version: "2.4.0".to_string(),
+            }),
+            probes: vec![],
+        }));
+        assert_eq!(res[5].1, BundleReturn::success(ContractClassification {
+            standard: ContractStandard::Unknown,
+            contract_version: None,
+            probes: vec![],
+        }));
+        assert!(matches!(res[6].1, BundleReturn::Error(_)));
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn stargate_system_errors_per_item() {
+        // MockQuerier does not support Stargate queries, so every item is a System Error
+        let deps = mock_dependencies();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StargateBundleQuery {
+            queries: vec![
+                ("/osmosis.gamm.v1beta1.Query/Pool".to_string(), Binary::from(b"\x08\x01")),
+                ("/cosmos.authz.v1beta1.Query/Grants".to_string(), Binary::default()),
+            ],
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<Binary>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[1].0, "/cosmos.authz.v1beta1.Query/Grants");
+        assert!(res.iter().all(|(_, r)| matches!(r, BundleReturn::Error(e) if e.starts_with("System Err"))));
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn ibc_channels() {
+        use std::marker::PhantomData;
+        use cosmwasm_std::{
+            ChannelResponse, IbcOrder, IbcQuery, ListChannelsResponse, PortIdResponse, Querier, QuerierResult, QueryRequest,
+            testing::mock_ibc_channel,
+        };
+        use crate::query::IbcChannels;
+
+        // MockQuerier does not support IBC queries, so a minimal querier stands in for the chain
+        struct IbcQuerier;
+        impl Querier for IbcQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let channel = mock_ibc_channel("channel-0", IbcOrder::Unordered, "ics20-1");
+                let res = match from_json::<QueryRequest<Empty>>(bin_request).unwrap() {
+                    QueryRequest::Ibc(IbcQuery::PortId {}) => to_json_binary(&PortIdResponse {
+                        port_id: channel.endpoint.port_id.clone(),
+                    }),
+                    QueryRequest::Ibc(IbcQuery::ListChannels { .. }) => to_json_binary(&ListChannelsResponse {
+                        channels: vec![channel],
+                    }),
+                    QueryRequest::Ibc(IbcQuery::Channel { channel_id, .. }) => to_json_binary(&ChannelResponse {
+                        channel: (channel_id == channel.endpoint.channel_id).then_some(channel),
+                    }),
+                    _ => return SystemResult::Err(SystemError::UnsupportedRequest { kind: "other".to_string() }),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+        }
+        let deps = OwnedDeps {
+            storage: MemoryStorage::new(),
+            api: MockApi::default(),
+            querier: IbcQuerier,
+            custom_query_type: PhantomData::<Empty>,
+        };
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::IbcChannelsBundleQuery {
+            list_channels: true,
+            list_port_id: None,
+            channels: vec![
+                ("my_port".to_string(), "channel-0".to_string()),
+                ("my_port".to_string(), "channel-9".to_string()),
+            ],
+        }).unwrap();
+        let res: IbcChannels = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Unordered, "ics20-1");
+        assert_eq!(res.port_id, BundleReturn::success("my_port".to_string()));
+        assert_eq!(res.list_channels, Some(BundleReturn::success(vec![channel.clone()])));
+        assert_eq!(res.channels, vec![
+            ("my_port".to_string(), "channel-0".to_string(), BundleReturn::success(Some(channel))),
+            ("my_port".to_string(), "channel-9".to_string(), BundleReturn::success(None)),
+        ]);
+    }
+
+    #[test]
+    fn block_context_and_min_height() {
+        let deps = mock_dependencies_with_balances(&[("alice", &coins(100, "ujuno"))]);
+        let env = mock_env();
+        let bank_query = || QueryMsg::BankBalancesBundleQuery {
+            addresses: vec!["alice".to_string()],
+            denoms: vec!["ujuno".to_string()],
+            all_balances: false,
+        };
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::WithMinHeight {
+            expected_min_height: env.block.height,
+            msg: Box::new(bank_query()),
+        }).unwrap();
+        let res: BundleResponse = from_json(res).unwrap();
+        assert_eq!(res.height, env.block.height);
+        assert_eq!(res.time, env.block.time);
+        assert_eq!(res.chain_id, env.block.chain_id);
+        let balances: Vec<(String, BankBalances)> = from_json(&res.data).unwrap();
+        assert_eq!(balances[0].1.balances, vec![("ujuno".to_string(), BalanceRes::success(Uint128::new(100)))]);
+
+        let err = query(deps.as_ref(), env.clone(), QueryMsg::WithMinHeight {
+            expected_min_height: env.block.height + 1,
+            msg: Box::new(bank_query()),
+        }).unwrap_err();
+        assert!(err.to_string().contains("Node is behind"));
+    }
+
+    #[cfg(feature = "cosmwasm_1_3")]
+    #[test]
+    fn bank_denom_metadata() {
+        use cosmwasm_std::{DenomMetadata, DenomUnit};
+
+        let mut deps = mock_dependencies();
+        let metadata = DenomMetadata {
+            base: "ujuno".to_string(),
+            display: "juno".to_string(),
+            symbol: "JUNO".to_string(),
+            denom_units: vec![
+                DenomUnit { denom: "ujuno".to_string(), exponent: 0, aliases: vec![] },
+                DenomUnit { denom: "juno".to_string(), exponent: 6, aliases: vec![] },
+            ],
+            ..Default::default()
+        };
+        deps.querier.set_denom_metadata(std::slice::from_ref(&metadata));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::BankDenomMetadataBundleQuery {
+            denoms: vec!["ujuno".to_string(), "uatom".to_string()],
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<DenomMetadata>)> = from_json(&from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0], ("ujuno".to_string(), BundleReturn::success(metadata)));
+        assert!(matches!(res[1].1, BundleReturn::Error(_)));
+    }
+
+    #[test]
+    fn cw721_portfolio_shared_budget() {
+        let deps = mock_deps_with_contracts(vec![], |contract, msg| {
+            let tokens: &[&str] = match contract {
+                "col1" => &["1", "2", "3"],
+                _ => &["a"],
+            };
+            match from_json(msg).unwrap() {
+                cw721::Cw721QueryMsg::Tokens { start_after, limit, .. } => to_json_binary(&cw721::TokensResponse {
+                    tokens: page_of(tokens, start_after, limit),
+                }).into(),
+                _ => ContractResult::Err("unsupported".to_string()),
+            }
+        });
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw721BundleQueryPortfolio {
+            owner: "owner".to_string(),
+            contracts: vec![
+                ("col1".to_string(), None),
+                ("col2".to_string(), Some("0".to_string())),
+                ("".to_string(), None),
+            ],
+            loop_limit: 2,
+            max_limit: Some(2),
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<Page<String>>)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        // Both queries are spent on col1, so col2 is returned with its cursor unchanged
+        assert_eq!(res[0].1, BundleReturn::success(Page {
+            items: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            next_start_after: Some("3".to_string()),
+            exhausted: true,
+        }));
+        assert_eq!(res[1].1, BundleReturn::success(Page {
+            items: vec![],
+            next_start_after: Some("0".to_string()),
+            exhausted: false,
+        }));
+        assert!(matches!(res[2].1, BundleReturn::Error(_)));
+    }
+
+    #[test]
+    fn cw20_all_allowances_shared_budget() {
+        use cw20::{AllAllowancesResponse, AllSpenderAllowancesResponse, AllowanceInfo, SpenderAllowanceInfo};
+
+        /// `AllSpenderAllowances` is served by cw20-base but missing from `Cw20QueryMsg` in cw20 1.1.0
+        #[cosmwasm_schema::cw_serde]
+        enum SpenderQueryMsg {
+            AllSpenderAllowances { spender: String, start_after: Option<String>, limit: Option<u32> },
+        }
+
+        let deps = mock_deps_with_contracts(vec![], |_, msg| {
+            if let Ok(Cw20QueryMsg::AllAllowances { owner, start_after, limit }) = from_json(msg) {
+                let spenders: &[&str] = if owner == "owner1" { &["s1", "s2", "s3"] } else { &["s1"] };
+                return to_json_binary(&AllAllowancesResponse {
+                    allowances: page_of(spenders, start_after, limit).into_iter().map(|spender| AllowanceInfo {
+                        spender,
+                        allowance: Uint128::new(1),
+                        expires: Expiration::Never {},
+                    }).collect(),
+                }).into();
+            }
+            match from_json(msg).unwrap() {
+                SpenderQueryMsg::AllSpenderAllowances { start_after, limit, .. } => to_json_binary(&AllSpenderAllowancesResponse {
+                    allowances: page_of(&["owner1", "owner2", "owner3"], start_after, limit).into_iter().map(|owner| SpenderAllowanceInfo {
+                        owner,
+                        allowance: Uint128::new(1),
+                        expires: Expiration::Never {},
+                    }).collect(),
+                }).into(),
+            }
+        });
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryAllAllowances {
+            owners: vec![("owner1".to_string(), None), ("owner2".to_string(), None)],
+            loop_limit: 2,
+            max_limit: Some(2),
+            contract: "token".to_string(),
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<Page<AllowanceInfo>>)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        // Both queries are spent on owner1, so owner2 is not reached
+        let BundleReturn::Success(owner1) = &res[0].1 else { panic!("expected allowances") };
+        assert_eq!(owner1.items.iter().map(|a| a.spender.as_str()).collect::<Vec<_>>(), vec!["s1", "s2", "s3"]);
+        assert!(owner1.exhausted);
+        assert_eq!(res[1].1, BundleReturn::success(Page { items: vec![], next_start_after: None, exhausted: false }));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryAllSpenderAllowances {
+            spenders: vec![("s1".to_string(), Some("owner1".to_string()))],
+            loop_limit: 1,
+            max_limit: Some(2),
+            contract: "token".to_string(),
+        }).unwrap();
+        let res: Vec<(String, BundleReturn<Page<SpenderAllowanceInfo>>)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        // A full page is not known to be the last one
+        let BundleReturn::Success(s1) = &res[0].1 else { panic!("expected allowances") };
+        assert_eq!(s1.items.iter().map(|a| a.owner.as_str()).collect::<Vec<_>>(), vec!["owner2", "owner3"]);
+        assert_eq!(s1.next_start_after, Some("owner3".to_string()));
+        assert!(!s1.exhausted);
+    }
+
+    #[test]
+    fn cw20_metadata_raw_and_smart_fallback() {
+        use cw20::{MarketingInfoResponse, MinterResponse};
+        use crate::query::{Cw20Metadata, Cw20MetadataRes};
+
+        // A cw20-base contract, whose `token_info` Item holds the minter
+        let mut token = MemoryStorage::new();
+        token.set(b"token_info", br#"{"name":"Token","symbol":"TKN","decimals":6,"total_supply":"100","mint":{"minter":"minter","cap":null}}"#);
+        cw2::set_contract_version(&mut token, "crates.io:cw20-base", "1.0.1").unwrap();
+
+        let deps = mock_deps_with_contracts(vec![("token", token), ("other", MemoryStorage::new())], |_, msg| {
+            match from_json(msg).unwrap() {
+                Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                    name: "Other".to_string(),
+                    symbol: "OTH".to_string(),
+                    decimals: 0,
+                    total_supply: Uint128::new(5),
+                }).into(),
+                Cw20QueryMsg::Minter {} => to_json_binary(&None::<MinterResponse>).into(),
+                _ => ContractResult::Err("unsupported".to_string()),
+            }
+        });
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryMetadata {
+            contracts: vec!["token".to_string(), "other".to_string(), "".to_string()],
+        }).unwrap();
+        let res: Vec<(String, Cw20MetadataRes)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1, Cw20MetadataRes::success(Cw20Metadata {
+            token_info: BundleReturn::success(TokenInfoResponse {
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                decimals: 6,
+                total_supply: Uint128::new(100),
+            }),
+            minter: BundleReturn::success(Some(MinterResponse { minter: "minter".to_string(), cap: None })),
+            marketing_info: BundleReturn::success(MarketingInfoResponse::default()),
+            version: BundleReturn::success(cw2::ContractVersion {
+                contract: "crates.io:cw20-base".to_string(),
+                version: "1.0.1".to_string(),
+            }),
+        }));
+
+        // Without a readable `token_info` every field falls back to a Smart Query, and fails on its own
+        let BundleReturn::Success(other) = &res[1].1 else { panic!("expected metadata") };
+        assert!(matches!(&other.token_info, BundleReturn::Success(info) if info.symbol == "OTH"));
+        assert_eq!(other.minter, BundleReturn::success(None));
+        assert!(matches!(other.marketing_info, BundleReturn::Error(_)));
+        assert_eq!(other.version, BundleReturn::error("Nonexistent key"));
+
+        assert!(matches!(res[2].1, BundleReturn::Error(_)));
+    }
+
+    #[test]
+    fn cw20_portfolio_modes() {
+        use crate::msg::QueryMode;
+
+        let mut token_a = MemoryStorage::new();
+        cw_storage_plus::Map::<&Addr, Uint128>::new("balance").save(&mut token_a, &Addr::unchecked("alice"), &Uint128::new(10)).unwrap();
+
+        let deps = mock_deps_with_contracts(vec![("token_a", token_a), ("token_b", MemoryStorage::new())], |contract, msg| {
+            match from_json(msg).unwrap() {
+                Cw20QueryMsg::Balance { .. } if contract == "token_a" => to_json_binary(&cw20::BalanceResponse {
+                    balance: Uint128::new(10),
+                }).into(),
+                _ => ContractResult::Err("unsupported".to_string()),
+            }
+        });
+
+        let query_portfolio = |mode: QueryMode| -> Vec<(String, BalanceRes)> {
+            let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20PortfolioQuery {
+                account: "alice".to_string(),
+                contracts: vec!["token_a".to_string(), "token_b".to_string(), "".to_string()],
+                mode,
+            }).unwrap();
+            from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap()
+        };
+
+        // A missing raw balance is zero, the same as cw20-base returns
+        let res = query_portfolio(QueryMode::Raw);
+        assert_eq!(res[0].1, BalanceRes::success(Uint128::new(10)));
+        assert_eq!(res[1].1, BalanceRes::success(Uint128::zero()));
+        assert!(matches!(res[2].1, BundleReturn::Error(_)));
+
+        let res = query_portfolio(QueryMode::Smart);
+        assert_eq!(res[0].1, BalanceRes::success(Uint128::new(10)));
+        assert!(matches!(res[1].1, BundleReturn::Error(_)));
+        assert!(matches!(res[2].1, BundleReturn::Error(_)));
+    }
+
+    #[test]
+    fn asset_balance_matrix() {
+        use crate::{msg::{AssetInfo, QueryMode}, query::AssetBalanceMatrix};
+
+        let mut token = MemoryStorage::new();
+        cw_storage_plus::Map::<&Addr, Uint128>::new("balance").save(&mut token, &Addr::unchecked("bob"), &Uint128::new(7)).unwrap();
+
+        let mut deps = mock_deps_with_contracts(vec![("token", token)], |_, _| ContractResult::Err("unsupported".to_string()));
+        deps.querier.update_balance("alice", coins(100, "ujuno"));
+
+        let assets = vec![
+            AssetInfo::Native("ujuno".to_string()),
+            AssetInfo::Cw20("token".to_string()),
+            AssetInfo::Cw20("".to_string()),
+        ];
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AssetBalancesBundleQuery {
+            accounts: vec!["alice".to_string(), "bob".to_string()],
+            assets: assets.clone(),
+            mode: QueryMode::Raw,
+        }).unwrap();
+        let res: AssetBalanceMatrix = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        // Each row follows the order of `assets`, an invalid cw20 only fails its own column
+        assert_eq!(res.assets, assets);
+        assert_eq!(res.balances[0].0, "alice");
+        assert_eq!(res.balances[0].1[..2], [BalanceRes::success(Uint128::new(100)), BalanceRes::success(Uint128::zero())]);
+        assert_eq!(res.balances[1].0, "bob");
+        assert_eq!(res.balances[1].1[..2], [BalanceRes::success(Uint128::zero()), BalanceRes::success(Uint128::new(7))]);
+        assert!(res.balances.iter().all(|(_, row)| matches!(row[2], BundleReturn::Error(_))));
+    }
+
+    #[test]
+    fn cw20_marketing_raw() {
+        use cw20::{EmbeddedLogo, Logo, LogoInfo, MarketingInfoResponse};
+        use crate::query::{Cw20LogoData, Cw20Marketing, Cw20MarketingRes};
+
+        let logo = cw_storage_plus::Item::<Logo>::new("logo");
+        let marketing_info = MarketingInfoResponse {
+            project: Some("project".to_string()),
+            logo: Some(LogoInfo::Embedded),
+            ..Default::default()
+        };
+
+        let mut svg = MemoryStorage::new();
+        logo.save(&mut svg, &Logo::Embedded(EmbeddedLogo::Svg(Binary::from(b"<svg>")))).unwrap();
+        cw_storage_plus::Item::<MarketingInfoResponse>::new("marketing_info").save(&mut svg, &marketing_info).unwrap();
+        let mut png = MemoryStorage::new();
+        logo.save(&mut png, &Logo::Embedded(EmbeddedLogo::Png(Binary::from(vec![0u8; 16])))).unwrap();
+        let mut url = MemoryStorage::new();
+        logo.save(&mut url, &Logo::Url("https://logo".to_string())).unwrap();
+
+        let deps = mock_deps_with_contracts(
+            vec![("svg", svg), ("png", png), ("url", url), ("none", MemoryStorage::new())],
+            |_, _| ContractResult::Err("unsupported".to_string())
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cw20BundleQueryMarketing {
+            contracts: vec!["svg".to_string(), "png".to_string(), "url".to_string(), "none".to_string()],
+            max_logo_size: 8,
+        }).unwrap();
+        let res: Vec<(String, Cw20MarketingRes)> = from_json(from_json::<BundleResponse>(&res).unwrap().data).unwrap();
+
+        assert_eq!(res[0].1, Cw20MarketingRes::success(Cw20Marketing {
+            marketing_info: BundleReturn::success(marketing_info),
+            logo: BundleReturn::success(Some(Cw20LogoData::Embedded {
+                mime_type: "image/svg+xml".to_string(),
+                data: Binary::from(b"<svg>"),
+            })),
+        }));
+
+        // The png is over `max_logo_size`, so only its size is returned
+        let BundleReturn::Success(png) = &res[1].1 else { panic!("expected marketing") };
+        assert_eq!(png.logo, BundleReturn::success(Some(Cw20LogoData::Skipped {
+            mime_type: "image/png".to_string(),
+            size: 16,
+        })));
+
+        let BundleReturn::Success(url) = &res[2].1 else { panic!("expected marketing") };
+        assert_eq!(url.logo, BundleReturn::success(Some(Cw20LogoData::Url("https://logo".to_string()))));
+
+        // Marketing info that was never set is the default, the same as cw20-base returns
+        assert_eq!(res[3].1, Cw20MarketingRes::success(Cw20Marketing {
+            marketing_info: BundleReturn::success(MarketingInfoResponse::default()),
+            logo: BundleReturn::success(None),
+        }));
     }
 }
@@ -52,6 +52,42 @@ impl ToRawKey for String {
     }
 }
 
+/// Composite `(String, String)` keys, such as `(&Addr, &Addr)` keys in cw-storage-plus
+/// - The first key is length prefixed the same way as the namespace
+impl ToRawKey for (String, String) {
+    fn to_raw_map_key(&self, namespace: &str) -> StdResult<Binary> {
+        if self.0.is_empty() || self.1.is_empty() {
+            return Err(StdError::GenericErr { msg: "Primary Key length cannot be 0".to_string() });
+        }
+
+        if namespace.is_empty() {
+            return Err(StdError::GenericErr { msg: "Namespace length cannot be 0".to_string() });
+        }
+
+        let namespace_bytes = namespace.as_bytes();
+        let encode_len_namespace = encode_length(namespace_bytes)?;
+        let first_bytes = self.0.as_bytes();
+        let encode_len_first = encode_length(first_bytes)?;
+        let second_bytes = self.1.as_bytes();
+
+        // [len(namespace) | namespace_bytes | len(first) | first_bytes | second_bytes]
+        let key: Vec<u8> = {
+            // Panics if 4 + nmsp.len() + first.len() + second.len() > isize::MAX
+            let mut mkey: Vec<u8> = Vec::with_capacity(4 + namespace_bytes.len() + first_bytes.len() + second_bytes.len());
+            mkey.extend_from_slice(encode_len_namespace.as_slice());
+            mkey.extend_from_slice(namespace_bytes);
+            mkey.extend_from_slice(encode_len_first.as_slice());
+            mkey.extend_from_slice(first_bytes);
+            mkey.extend_from_slice(second_bytes);
+            mkey
+        };
+
+        let bin_key = Binary::from(key);
+
+        Ok(bin_key)
+    }
+}
+
 macro_rules! to_raw_key_uint {
     (for $($t:ty),+) => {
         $(impl ToRawKey for $t {
@@ -1,5 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+#[cfg(feature = "stargate")]
 use cosmwasm_std::Binary;
+use crate::query::BundleResponse;
 
 #[cw_serde]
 pub struct InstantiateMsg {}
@@ -7,55 +10,211 @@ pub struct InstantiateMsg {}
 #[cw_serde]
 pub enum ExecuteMsg {}
 
+/// Every query responds with a [BundleResponse], whose `data` holds the JSON encoded result of the bundle
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
     Cw721BundleQuerySmart {
         token_ids: Vec<String>,
         contract: String
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
     Cw721BundleQueryRaw {
         token_ids: Vec<String>,
-        contract: String
+        contract: String,
+        layout: Option<Cw721Layout>,
+        decode: Option<bool>
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
     Cw721BundleQueryIds {
         loop_limit: u32,
         max_limit: Option<u32>,
         contract: String,
         start_after: Option<String>
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
+    Cw721BundleVerifyOwnership {
+        owner: String,
+        tokens: Vec<(String, String)>,
+        layout: Option<Cw721Layout>
+    },
+    #[returns(BundleResponse)]
+    Cw721BundleQueryPortfolio {
+        owner: String,
+        /// (contract, start_after)
+        contracts: Vec<(String, Option<String>)>,
+        loop_limit: u32,
+        max_limit: Option<u32>
+    },
+    #[returns(BundleResponse)]
+    Cw721BundleQueryTraitHistogram {
+        selection: Cw721TokenSelection,
+        contract: String
+    },
+    #[returns(BundleResponse)]
+    Cw2981BundleQueryRoyalties {
+        token_ids: Vec<String>,
+        sale_price: Uint128,
+        contract: String
+    },
+    #[returns(BundleResponse)]
     Cw20BundleQuerySmart {
         accounts: Vec<String>,
         contract: String
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
     Cw20BundleQueryRaw {
         accounts: Vec<String>,
         contract: String
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
+    Cw20AllowanceBundleQuerySmart {
+        /// (owner, spender)
+        allowances: Vec<(String, String)>,
+        contract: String
+    },
+    #[returns(BundleResponse)]
+    Cw20AllowanceBundleQueryRaw {
+        /// (owner, spender)
+        allowances: Vec<(String, String)>,
+        contract: String
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryAllAllowances {
+        /// (owner, start_after)
+        owners: Vec<(String, Option<String>)>,
+        loop_limit: u32,
+        max_limit: Option<u32>,
+        contract: String
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryAllSpenderAllowances {
+        /// (spender, start_after)
+        spenders: Vec<(String, Option<String>)>,
+        loop_limit: u32,
+        max_limit: Option<u32>,
+        contract: String
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryMetadata {
+        contracts: Vec<String>
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryMarketing {
+        contracts: Vec<String>,
+        max_logo_size: u32
+    },
+    #[returns(BundleResponse)]
+    Cw20PortfolioQuery {
+        account: String,
+        contracts: Vec<String>,
+        mode: QueryMode
+    },
+    #[returns(BundleResponse)]
     Cw20BundleQueryAccounts {
         loop_limit: u32,
+        max_limit: Option<u32>,
+        contract: String,
+        start_after: Option<String>
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryHolderSnapshot {
+        max_items: u32,
+        max_limit: Option<u32>,
+        contract: String,
+        start_after: Option<String>
+    },
+    #[returns(BundleResponse)]
+    Cw20BundleQueryHolderStats {
+        loop_limit: u32,
+        max_limit: Option<u32>,
+        top_n: u32,
+        bucket_bounds: Vec<Uint128>,
         contract: String,
         start_after: Option<String>
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
+    Cw20BundleQuerySupplyAudit {
+        loop_limit: u32,
+        max_limit: Option<u32>,
+        contract: String,
+        cursor: Option<Cw20AuditCursor>
+    },
+    #[returns(BundleResponse)]
+    AssetBalancesBundleQuery {
+        accounts: Vec<String>,
+        assets: Vec<AssetInfo>,
+        mode: QueryMode
+    },
+    #[returns(BundleResponse)]
+    BankBalancesBundleQuery {
+        addresses: Vec<String>,
+        denoms: Vec<String>,
+        all_balances: bool
+    },
+    #[cfg(feature = "cosmwasm_1_1")]
+    #[returns(BundleResponse)]
+    BankSupplyBundleQuery {
+        denoms: Vec<String>
+    },
+    #[cfg(feature = "cosmwasm_1_3")]
+    #[returns(BundleResponse)]
+    BankDenomMetadataBundleQuery {
+        denoms: Vec<String>
+    },
+    #[returns(BundleResponse)]
+    WasmContractInfoBundleQuery {
+        contracts: Vec<String>
+    },
+    #[returns(BundleResponse)]
+    ContractBundleQueryClassify {
+        contracts: Vec<String>
+    },
+    #[cfg(feature = "staking")]
+    #[returns(BundleResponse)]
+    StakingDelegationsBundleQuery {
+        delegators: Vec<String>,
+        validator: Option<String>
+    },
+    #[cfg(feature = "staking")]
+    #[returns(BundleResponse)]
+    StakingValidatorsBundleQuery {
+        validators: Vec<String>
+    },
+    #[cfg(feature = "stargate")]
+    #[returns(BundleResponse)]
+    StargateBundleQuery {
+        /// (path, protobuf data)
+        queries: Vec<(String, Binary)>
+    },
+    #[cfg(feature = "stargate")]
+    #[returns(BundleResponse)]
+    IbcChannelsBundleQuery {
+        list_channels: bool,
+        list_port_id: Option<String>,
+        /// (port, channel)
+        channels: Vec<(String, String)>
+    },
+    #[returns(BundleResponse)]
     GenericStringBundleQueryRaw {
         keys: Vec<String>,
         namespace: String,
         contract: String,
     },
-    #[returns(Binary)]
+    #[returns(BundleResponse)]
     GenericUIntBundleQueryRaw {
         keys: Vec<u64>,
         keytype: IntType,
         namespace: String,
         contract: String,
     },
+    /// Runs `msg` only if the node has reached `expected_min_height`
+    #[returns(BundleResponse)]
+    WithMinHeight {
+        expected_min_height: u64,
+        msg: Box<QueryMsg>
+    },
 }
 
 #[cw_serde]
@@ -65,4 +224,69 @@ pub enum IntType {
     U32,
     U64,
     U128
-}
\ No newline at end of file
+}
+
+/// Selects between Smart Queries and Raw Queries of a known storage layout
+#[cw_serde]
+pub enum QueryMode {
+    Smart,
+    Raw
+}
+
+/// A native denom or cw20 contract address, in the style of cw-asset
+#[cw_serde]
+pub enum AssetInfo {
+    Native(String),
+    Cw20(String)
+}
+
+/// Tokens to run a cw721 query over, either explicit token_ids or a window of `AllTokens` pages
+#[cw_serde]
+pub enum Cw721TokenSelection {
+    TokenIds(Vec<String>),
+    AllTokens {
+        loop_limit: u32,
+        max_limit: Option<u32>,
+        start_after: Option<String>
+    }
+}
+
+/// Continues a cw20 supply audit, carrying the sum of every balance read so far
+#[cw_serde]
+#[derive(Default)]
+pub struct Cw20AuditCursor {
+    pub start_after: Option<String>,
+    pub running_total: Uint128,
+}
+
+/// Storage layout of a cw721 variant, used to select the `tokens` namespace for raw queries
+/// - cw721-base 0.15 through 0.18 and sg721-base all store `TokenInfo` under the `tokens` namespace,
+///   the presets name the variant so layouts that diverge in later releases can be told apart
+/// - `Custom` is for forks that rename the namespace
+#[cw_serde]
+#[derive(Default)]
+pub enum Cw721Layout {
+    Cw721BaseV15,
+    Cw721BaseV16,
+    #[default]
+    Cw721BaseV17,
+    Cw721BaseV18,
+    Sg721Base,
+    Custom {
+        tokens: String,
+    }
+}
+
+impl Cw721Layout {
+    /// Namespace of the `tokens` IndexedMap
+    pub fn tokens_namespace(&self) -> &str {
+        match self {
+            Cw721Layout::Cw721BaseV15
+            | Cw721Layout::Cw721BaseV16
+            | Cw721Layout::Cw721BaseV17
+            | Cw721Layout::Cw721BaseV18
+            | Cw721Layout::Sg721Base => "tokens",
+            Cw721Layout::Custom { tokens } => tokens.as_str(),
+        }
+    }
+}
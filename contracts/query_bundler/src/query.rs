@@ -1,20 +1,60 @@
+use std::collections::BTreeMap;
 use cosmwasm_schema::cw_serde;
-use cw20::{AllAccountsResponse, BalanceResponse};
+use cw20::{
+    AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse,
+    AllowanceInfo, AllowanceResponse, BalanceResponse, EmbeddedLogo, Expiration, Logo, MarketingInfoResponse,
+    MinterResponse, SpenderAllowanceInfo, TokenInfoResponse
+};
+use cw2::ContractVersion;
 use serde::{Serialize, de::DeserializeOwned};
 use crate::{
     error::ErrorToMsg, 
-    encoding::ToRawKey, msg::IntType
+    encoding::ToRawKey, msg::{IntType, QueryMode, AssetInfo, Cw721Layout, Cw721TokenSelection, Cw20AuditCursor}
 };
 use cosmwasm_std::{
-    to_binary, Binary, Deps, QueryRequest, Empty,
-    StdResult, WasmQuery, StdError, ContractResult
+    to_json_binary, from_json, Addr, Binary, BlockInfo, Coin, Deps, QueryRequest, Empty,
+    StdResult, WasmQuery, StdError, ContractResult, Uint128, ContractInfoResponse, Timestamp
 };
-use cw721::{NftInfoResponse, TokensResponse};
+use cw721::{Approval, NftInfoResponse, TokensResponse};
+use cw721_base::state::TokenInfo;
 use cw721_metadata_onchain::Extension;
+use cw2981_royalties::msg::{Cw2981QueryMsg, RoyaltiesInfoResponse};
+#[cfg(feature = "cosmwasm_1_2")]
+use cosmwasm_std::CodeInfoResponse;
+#[cfg(feature = "cosmwasm_1_3")]
+use cosmwasm_std::DenomMetadata;
+#[cfg(feature = "stargate")]
+use cosmwasm_std::{
+    ChannelResponse, IbcChannel, IbcQuery, ListChannelsResponse, PortIdResponse, SystemResult
+};
+#[cfg(feature = "staking")]
+use cosmwasm_std::{Delegation, FullDelegation, Validator};
+
+/// Max page size of `AllTokens` and `Tokens` in cw721-base
+pub const CW721_MAX_LIMIT: u32 = 100;
+/// Max page size of `AllAccounts` and `AllAllowances` in cw20-base
+pub const CW20_MAX_LIMIT: u32 = 30;
+
+/// Characteristic Items probed by [contract_bundle_query_classify], in order of precedence
+/// - cw4-group stores `members` as a Map, so its `total` weight Item is probed instead
+const CLASSIFY_PROBES: [(&str, ContractStandard); 4] = [
+    ("num_tokens", ContractStandard::Cw721),
+    ("token_info", ContractStandard::Cw20),
+    ("total", ContractStandard::Cw4Group),
+    ("voting_module", ContractStandard::Dao),
+];
 
 pub type QueryResRaw = BundleReturn<String>;
+pub type Cw721QueryResRaw = BundleReturn<Cw721RawTokenInfo>;
+pub type Cw721OwnershipRes = BundleReturn<Cw721Ownership>;
+pub type Cw721PortfolioRes = BundleReturn<Page<String>>;
 pub type Cw721QueryResSmart = BundleReturn<NftInfoResponse<Extension>>;
 pub type Cw20QueryResSmart = BundleReturn<BalanceResponse>;
+pub type BalanceRes = BundleReturn<Uint128>;
+pub type Cw20AllowanceRes = BundleReturn<Cw20AllowanceInfo>;
+pub type Cw20MetadataRes = BundleReturn<Cw20Metadata>;
+pub type Cw20MarketingRes = BundleReturn<Cw20Marketing>;
+pub type Cw2981QueryResSmart = BundleReturn<RoyaltiesInfoResponse>;
 
 // ------------------------------------------------------------------------
 // -------------------------------------------------------- Query Responses
@@ -25,300 +65,1944 @@ pub enum BundleReturn<T> {
     Error(String)
 }
 
-impl<T> BundleReturn<T>
-where
-    T: Sized + Serialize + DeserializeOwned
-{
-    pub fn success(val: T) -> Self {
-        Self::Success(val)
-    }
+impl<T> BundleReturn<T>
+where
+    T: Sized + Serialize + DeserializeOwned
+{
+    pub fn success(val: T) -> Self {
+        Self::Success(val)
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self::Error(msg.into())
+    }
+}
+
+/// Envelope of every bundle query response
+/// - `height`, `time` and `chain_id` are those of the block the query was run against
+/// - `data` is the JSON encoded response of the bundle query, which serializes as a base64 string
+///   and is decoded with `from_json` into the result type of the inner query
+#[cw_serde]
+pub struct BundleResponse {
+    pub height: u64,
+    pub time: Timestamp,
+    pub chain_id: String,
+    pub data: Binary,
+}
+
+impl BundleResponse {
+    pub fn new(block: &BlockInfo, data: Binary) -> Self {
+        Self {
+            height: block.height,
+            time: block.time,
+            chain_id: block.chain_id.clone(),
+            data
+        }
+    }
+}
+
+/// Common envelope of enumeration queries
+/// - `next_start_after` is the cursor to resume from
+/// - `exhausted` is true once every item has been returned
+#[cw_serde]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_start_after: Option<String>,
+    pub exhausted: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(start_after: Option<String>) -> Self {
+        Self {
+            items: vec![],
+            next_start_after: start_after,
+            exhausted: false
+        }
+    }
+}
+
+/// Decoded value of a cw721 `tokens` entry
+/// - `extension` is returned as JSON since its shape is collection specific
+#[cw_serde]
+pub struct Cw721RawTokenInfo {
+    pub owner: String,
+    pub approvals: Vec<Approval>,
+    pub token_uri: Option<String>,
+    pub extension: Binary,
+}
+
+#[cw_serde]
+pub enum Cw721Ownership {
+    Owned,
+    NotOwned,
+    Missing
+}
+
+/// Response of [cw721_bundle_verify_ownership]
+/// - `all_owned` is only true if every `(collection, token_id)` pair is `Owned`
+#[cw_serde]
+pub struct Cw721OwnershipVerification {
+    pub all_owned: bool,
+    pub tokens: Vec<(String, String, Cw721OwnershipRes)>,
+}
+
+/// Trait counts returned by [cw721_bundle_query_trait_histogram]
+/// - `token_count` is the number of tokens whose metadata was counted
+/// - `traits` holds `(trait_type, value, count)`, sorted by trait_type then value
+/// - `next_start_after` is the cursor of the next `AllTokens` window, if one was used
+/// - `exhausted` is true once every token was read, and always true for `TokenIds`
+#[cw_serde]
+pub struct TraitHistogram {
+    pub token_count: u64,
+    pub traits: Vec<(String, String, u64)>,
+    pub errors: Vec<(String, String)>,
+    pub next_start_after: Option<String>,
+    pub exhausted: bool,
+}
+
+impl TraitHistogram {
+    /// Combines the histogram of another batch into this one
+    /// - The cursor is taken from `other`, so batches should be merged in order
+    pub fn merge(&mut self, other: TraitHistogram) {
+        let mut traits: BTreeMap<(String, String), u64> = self.traits
+            .drain(..)
+            .map(|(trait_type, value, count)| ((trait_type, value), count))
+            .collect();
+
+        for (trait_type, value, count) in other.traits.into_iter() {
+            *traits.entry((trait_type, value)).or_default() += count;
+        }
+
+        self.token_count += other.token_count;
+        self.traits = traits.into_iter().map(|((trait_type, value), count)| (trait_type, value, count)).collect();
+        self.errors.extend(other.errors);
+        self.next_start_after = other.next_start_after;
+        self.exhausted = other.exhausted;
+    }
+}
+
+/// A cw20 allowance, with `expired` checked against the block it was queried at
+#[cw_serde]
+pub struct Cw20AllowanceInfo {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+    pub expired: bool,
+}
+
+impl Cw20AllowanceInfo {
+    pub fn new(val: AllowanceResponse, block: &BlockInfo) -> Self {
+        Self {
+            allowance: val.allowance,
+            expired: val.expires.is_expired(block),
+            expires: val.expires,
+        }
+    }
+}
+
+/// Holder aggregates returned by [cw20_bundle_query_holder_stats]
+/// - `top_holders` holds the `top_n` largest `(account, balance)`, sorted by balance descending
+/// - `buckets` holds `(lower_bound, count)` for each balance bucket
+/// - `next_start_after` is the cursor of the next window, `exhausted` is true once every account was read
+#[cw_serde]
+pub struct Cw20HolderStats {
+    pub holder_count: u64,
+    pub non_zero_count: u64,
+    pub total_balance: Uint128,
+    pub top_n: u32,
+    pub top_holders: Vec<(String, Uint128)>,
+    pub buckets: Vec<(Uint128, u64)>,
+    pub errors: Vec<(String, String)>,
+    pub next_start_after: Option<String>,
+    pub exhausted: bool,
+}
+
+impl Cw20HolderStats {
+    /// Combines the stats of another window into these
+    /// - Both windows must use the same bucket bounds
+    /// - The cursor is taken from `other`, so windows should be merged in order
+    pub fn merge(&mut self, other: Cw20HolderStats) -> StdResult<()> {
+        if self.buckets.len() != other.buckets.len()
+            || self.buckets.iter().zip(other.buckets.iter()).any(|(a, b)| a.0 != b.0)
+        {
+            return Err(StdError::generic_err("Cannot merge stats with different bucket bounds"));
+        }
+
+        self.holder_count += other.holder_count;
+        self.non_zero_count += other.non_zero_count;
+        self.total_balance = self.total_balance.checked_add(other.total_balance)?;
+        self.top_n = self.top_n.max(other.top_n);
+        self.top_holders.extend(other.top_holders);
+        self.sort_top_holders();
+
+        for (bucket, other_bucket) in self.buckets.iter_mut().zip(other.buckets) {
+            bucket.1 += other_bucket.1;
+        }
+
+        self.errors.extend(other.errors);
+        self.next_start_after = other.next_start_after;
+        self.exhausted = other.exhausted;
+
+        Ok(())
+    }
+
+    fn sort_top_holders(&mut self) {
+        self.top_holders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        self.top_holders.truncate(self.top_n as usize);
+    }
+}
+
+/// Progress of [cw20_bundle_query_supply_audit]
+/// - `cursor` continues the audit and carries the running total of balances
+/// - `reconciled` is only set once `exhausted`, and is true if the running total equals `total_supply`
+#[cw_serde]
+pub struct Cw20SupplyAudit {
+    pub accounts_read: u64,
+    pub cursor: Cw20AuditCursor,
+    pub total_supply: Uint128,
+    pub exhausted: bool,
+    pub reconciled: Option<bool>,
+}
+
+/// Balances returned by [asset_balances_bundle_query]
+/// - `balances` holds `(account, row)`, where each row has one balance per asset in the order of `assets`
+#[cw_serde]
+pub struct AssetBalanceMatrix {
+    pub assets: Vec<AssetInfo>,
+    pub balances: Vec<(String, Vec<BalanceRes>)>,
+}
+
+/// Native balances of one address returned by [bank_balances_bundle_query]
+/// - `balances` holds `(denom, amount)` for each requested denom
+/// - `all_balances` is only set if it was requested
+#[cw_serde]
+pub struct BankBalances {
+    pub balances: Vec<(String, BalanceRes)>,
+    pub all_balances: Option<BundleReturn<Vec<Coin>>>,
+}
+
+/// Metadata of one cw20 contract returned by [cw20_bundle_query_metadata]
+/// - `minter` is `None` if the token is not mintable
+#[cw_serde]
+pub struct Cw20Metadata {
+    pub token_info: BundleReturn<TokenInfoResponse>,
+    pub minter: BundleReturn<Option<MinterResponse>>,
+    pub marketing_info: BundleReturn<MarketingInfoResponse>,
+    pub version: BundleReturn<ContractVersion>,
+}
+
+/// Value of the cw20-base `token_info` Item, which also holds the minter
+#[cw_serde]
+struct Cw20StoredTokenInfo {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: Uint128,
+    mint: Option<Cw20StoredMinter>,
+}
+
+#[cw_serde]
+struct Cw20StoredMinter {
+    minter: Addr,
+    cap: Option<Uint128>,
+}
+
+/// Marketing info and logo of one cw20 contract returned by [cw20_bundle_query_marketing]
+/// - `logo` is `None` if no logo was set
+#[cw_serde]
+pub struct Cw20Marketing {
+    pub marketing_info: BundleReturn<MarketingInfoResponse>,
+    pub logo: BundleReturn<Option<Cw20LogoData>>,
+}
+
+#[cw_serde]
+pub enum Cw20LogoData {
+    Url(String),
+    Embedded {
+        mime_type: String,
+        data: Binary,
+    },
+    /// Embedded logo over the caller's size cap
+    Skipped {
+        mime_type: String,
+        size: u64,
+    },
+}
+
+/// Wasm info of one contract returned by [wasm_contract_info_bundle_query]
+/// - `code_info` is shared by every contract with the same `code_id`
+#[cw_serde]
+pub struct WasmContractInfo {
+    pub contract_info: ContractInfoResponse,
+    #[cfg(feature = "cosmwasm_1_2")]
+    pub code_info: BundleReturn<CodeInfoResponse>,
+}
+
+/// Standards recognized by [contract_bundle_query_classify]
+#[cw_serde]
+pub enum ContractStandard {
+    Cw721,
+    Cw20,
+    Cw4Group,
+    Dao,
+    Unknown
+}
+
+/// Best guess of the standard of one contract returned by [contract_bundle_query_classify]
+/// - `contract_version` is the cw2 `contract_info` Item, `None` if the contract does not set it
+/// - `probes` holds each probed key that exists in the contract's storage
+#[cw_serde]
+pub struct ContractClassification {
+    pub standard: ContractStandard,
+    pub contract_version: Option<ContractVersion>,
+    pub probes: Vec<String>,
+}
+
+/// Delegations of one delegator returned by [staking_delegations_bundle_query]
+/// - `All` is returned when no validator filter is given
+/// - `Validator` holds the `FullDelegation` to the filtered validator, or `None` if there is none
+#[cfg(feature = "staking")]
+#[cw_serde]
+pub enum StakingDelegations {
+    All(Vec<Delegation>),
+    Validator(Option<FullDelegation>),
+}
+
+/// Validator set snapshot returned by [staking_validators_bundle_query]
+/// - `validators` holds `(address, validator)` for each requested address,
+///   `None` if the address is not a validator
+#[cfg(feature = "staking")]
+#[cw_serde]
+pub struct StakingValidators {
+    pub bonded_denom: BundleReturn<String>,
+    pub all_validators: BundleReturn<Vec<Validator>>,
+    pub validators: Vec<(String, BundleReturn<Option<Validator>>)>,
+}
+
+/// IBC state of this contract returned by [ibc_channels_bundle_query]
+/// - `list_channels` is only set if it was requested
+/// - `channels` holds `(port, channel, info)` for each requested pair, `None` if the channel does not exist
+#[cfg(feature = "stargate")]
+#[cw_serde]
+pub struct IbcChannels {
+    pub port_id: BundleReturn<String>,
+    pub list_channels: Option<BundleReturn<Vec<IbcChannel>>>,
+    pub channels: Vec<(String, String, BundleReturn<Option<IbcChannel>>)>,
+}
+
+/// cw20-base serves `AllSpenderAllowances`, but it is missing from `Cw20QueryMsg` in cw20 1.1.0
+#[cw_serde]
+enum Cw20SpenderQueryMsg {
+    AllSpenderAllowances {
+        spender: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    }
+}
+
+// ------------------------------------------------------------------------
+// ------------------------------------------------------------ Raw Helpers
+// ------------------------------------------------------------------------
+
+/// Raw Queries a single `key` of the provided contract
+/// - Only returns `Err` if request serialization fails or the query results in a System Error
+/// - Contract Errors are returned as `ContractResult::Err` so they can be handled per item
+/// - An empty `Binary` means the key did not exist
+fn query_raw_key(
+    deps: Deps,
+    contract: &str,
+    key: Binary
+) -> StdResult<ContractResult<Binary>> {
+
+    let request: QueryRequest<Empty> = WasmQuery::Raw {
+        contract_addr: contract.to_string(),
+        key
+    }.into();
+
+    let raw = cosmwasm_std::to_json_vec(&request).map_err(|serialize_err| {
+        StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
+    })?;
+
+    deps.querier.raw_query(&raw)
+        .into_result()
+        .map_err(|e| StdError::generic_err(format!("System Err: {}", e)))
+}
+
+/// Converts the result of a raw query into a base64 encoded [QueryResRaw]
+fn to_query_res_raw(val: ContractResult<Binary>) -> QueryResRaw {
+    match val {
+        ContractResult::Err(err) => QueryResRaw::error(err),
+        ContractResult::Ok(val) => {
+            // Handle null byte, which means key did not exist
+            if val.is_empty() {
+                QueryResRaw::error("Nonexistent key")
+            } else {
+                QueryResRaw::success(val.to_base64())
+            }
+        }
+    }
+}
+
+/// Decodes the result of a raw query into `T`, returning `None` if the key did not exist
+fn decode_raw<T>(val: ContractResult<Binary>) -> BundleReturn<Option<T>>
+where
+    T: Serialize + DeserializeOwned
+{
+    match val {
+        ContractResult::Err(err) => BundleReturn::error(err),
+        // Handle null byte, which means key did not exist
+        ContractResult::Ok(val) if val.is_empty() => BundleReturn::success(None),
+        ContractResult::Ok(val) => match from_json::<T>(&val) {
+            Err(e) => BundleReturn::error(e.to_msg()),
+            Ok(val) => BundleReturn::success(Some(val))
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// ---------------------------------------------------------- Smart Helpers
+// ------------------------------------------------------------------------
+
+/// Smart Queries the provided contract with `msg`
+/// - Only returns `Err` if base64 serialization of `msg` fails
+/// - Query Errors are returned as [BundleReturn::Error] so they can be handled per item
+fn query_smart<T>(
+    deps: Deps,
+    contract: &str,
+    msg: &impl Serialize
+) -> StdResult<BundleReturn<T>>
+where
+    T: Serialize + DeserializeOwned
+{
+    let response: StdResult<T> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: contract.to_string(),
+        msg: to_json_binary(msg)?
+    }));
+
+    match response {
+        Err(e) => Ok(BundleReturn::error(e.to_msg())),
+        Ok(val) => Ok(BundleReturn::success(val))
+    }
+}
+
+/// Validates a per-page `limit` before any query is made, defaulting to `max`
+fn validate_page_limit(limit: Option<u32>, max: u32) -> StdResult<u32> {
+    match limit.unwrap_or(max) {
+        0 => Err(StdError::generic_err("Page limit cannot be 0")),
+        limit if limit > max => Err(StdError::generic_err(format!("Page limit cannot be > {max}"))),
+        limit => Ok(limit)
+    }
+}
+
+/// Response of a paginated Smart Query
+trait PageResponse: DeserializeOwned {
+    type Item;
+
+    fn into_items(self) -> Vec<Self::Item>;
+
+    /// Returns the `start_after` value that continues after `item`
+    fn cursor(item: &Self::Item) -> String;
+}
+
+impl PageResponse for TokensResponse {
+    type Item = String;
+
+    fn into_items(self) -> Vec<String> {
+        self.tokens
+    }
+
+    fn cursor(item: &String) -> String {
+        item.clone()
+    }
+}
+
+impl PageResponse for AllAccountsResponse {
+    type Item = String;
+
+    fn into_items(self) -> Vec<String> {
+        self.accounts
+    }
+
+    fn cursor(item: &String) -> String {
+        item.clone()
+    }
+}
+
+impl PageResponse for AllAllowancesResponse {
+    type Item = AllowanceInfo;
+
+    fn into_items(self) -> Vec<AllowanceInfo> {
+        self.allowances
+    }
+
+    fn cursor(item: &AllowanceInfo) -> String {
+        item.spender.clone()
+    }
+}
+
+impl PageResponse for AllSpenderAllowancesResponse {
+    type Item = SpenderAllowanceInfo;
+
+    fn into_items(self) -> Vec<SpenderAllowanceInfo> {
+        self.allowances
+    }
+
+    fn cursor(item: &SpenderAllowanceInfo) -> String {
+        item.owner.clone()
+    }
+}
+
+/// Loops over a paginated Smart Query until `budget` queries have been made or every item was returned
+/// - `budget` is decremented per query, so it can be shared across several calls
+/// - `to_msg` serializes the query for a cursor
+fn query_pages<R>(
+    deps: Deps,
+    contract: &str,
+    budget: &mut u32,
+    limit: u32,
+    start_after: Option<String>,
+    to_msg: impl Fn(Option<String>) -> StdResult<Binary>
+) -> StdResult<Page<R::Item>>
+where
+    R: PageResponse
+{
+    let mut page = Page::new(start_after);
+
+    while *budget > 0 {
+
+        *budget -= 1;
+
+        let response: R = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.to_string(),
+            msg: to_msg(page.next_start_after.clone())?
+        }))?;
+
+        let items = response.into_items();
+
+        // Update start_after for next query
+        if let Some(last) = items.last() {
+            page.next_start_after = Some(R::cursor(last));
+        }
+
+        // If response length is less than limit, every item has been returned
+        let exhausted = items.len() < limit as usize;
+
+        page.items.extend(items);
+
+        if exhausted {
+            page.exhausted = true;
+            break;
+        }
+    }
+
+    Ok(page)
+}
+
+// ------------------------------------------------------------------------
+// ------------------------------------------------------------ Query Logic
+// ------------------------------------------------------------------------
+
+// ----------------------------------------------------------------- CW-721
+
+/// Smart Queries the provided contract with `Cw721QueryMsg::NftInfo`
+/// - This function requires deserializing successful query responses into a provided type
+/// - The type used here is [Extension](https://github.com/CosmWasm/cw-nfts/blob/f8600e6a760ce6ad340ce286262c55f471b2fb70/contracts/cw721-metadata-onchain/src/lib.rs#L31)
+///   from the default `cw721-metadata-onchain` contract, but could be replaced with your own type
+pub fn cw721_bundle_query_smart(
+    deps: Deps,
+    token_ids: Vec<String>,
+    contract: String
+) -> StdResult<Binary> {
+    
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    let mut res: Vec<(String, Cw721QueryResSmart)> = Vec::with_capacity(token_ids.len());
+
+    for id in token_ids.iter() {
+
+        // The entire call will fail if base64 serialization fails for -any- Query Msg
+        let msg = to_json_binary(&cw721::Cw721QueryMsg::NftInfo {
+            token_id: id.clone()
+        })?;
+
+        // I do not propogate Query Errors, instead handle them individually
+        // so that successful Query Responses can still be returned
+        let response: StdResult<NftInfoResponse<Extension>> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.clone(),
+            msg
+        }));
+
+        match response {
+            Err(e) => res.push((id.to_owned(), Cw721QueryResSmart::error(e.to_msg()))),
+            Ok(val) => res.push((id.to_owned(), Cw721QueryResSmart::success(val)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+
+/// Raw Queries the provided contract and token_id's
+/// - `layout` selects the `tokens` namespace of the cw721 variant, defaults to [Cw721Layout::Cw721BaseV17]
+/// - Values are returned base64 encoded, unless `decode` is set
+/// - With `decode`, values that do not decode as a cw721 `TokenInfo` are returned as errors
+pub fn cw721_bundle_query_raw(
+    deps: Deps,
+    token_ids: Vec<String>,
+    contract: String,
+    layout: Option<Cw721Layout>,
+    decode: Option<bool>
+) -> StdResult<Binary> {
+
+    let _validate = deps.api.addr_validate(contract.as_str())?;
+
+    let layout = layout.unwrap_or_default();
+
+    if decode.unwrap_or(false) {
+        let mut res: Vec<(String, Cw721QueryResRaw)> = Vec::with_capacity(token_ids.len());
+
+        for id in token_ids.iter() {
+
+            let response = match query_cw721_token_info_raw(deps, &contract, id, &layout)? {
+                BundleReturn::Error(e) => Cw721QueryResRaw::error(e),
+                BundleReturn::Success(None) => Cw721QueryResRaw::error("Nonexistent key"),
+                BundleReturn::Success(Some(info)) => Cw721QueryResRaw::success(info)
+            };
+
+            res.push((id.to_owned(), response));
+        }
+
+        return to_json_binary(&res);
+    }
+
+    let mut res: Vec<(String, QueryResRaw)> = Vec::with_capacity(token_ids.len());
+
+    for id in token_ids.iter() {
+
+        // The entire call will fail if key encoding fails for -any- token_id
+        let key = id.to_string().to_raw_map_key(layout.tokens_namespace())?;
+
+        let response = to_query_res_raw(query_raw_key(deps, &contract, key)?);
+
+        res.push((id.to_owned(), response));
+    }
+
+    to_json_binary(&res)
+}
+
+/// Raw Queries the `tokens` entry of a single token_id, returning `None` if it does not exist
+/// - Only returns `Err` if key encoding or request serialization fails, or on a System Error
+fn query_cw721_token_info_raw(
+    deps: Deps,
+    contract: &str,
+    token_id: &str,
+    layout: &Cw721Layout
+) -> StdResult<BundleReturn<Option<Cw721RawTokenInfo>>> {
+
+    let key = token_id.to_string().to_raw_map_key(layout.tokens_namespace())?;
+
+    let response = match query_raw_key(deps, contract, key)? {
+        ContractResult::Err(err) => BundleReturn::error(err),
+        // Handle null byte, which means key did not exist
+        ContractResult::Ok(val) if val.is_empty() => BundleReturn::success(None),
+        ContractResult::Ok(val) => match decode_cw721_token_info(&val) {
+            Err(e) => BundleReturn::error(e.to_msg()),
+            Ok(info) => BundleReturn::success(Some(info))
+        }
+    };
+
+    Ok(response)
+}
+
+/// Decodes a raw `tokens` value, which has the cw721-base `TokenInfo` shape
+fn decode_cw721_token_info(val: &Binary) -> StdResult<Cw721RawTokenInfo> {
+    let info: TokenInfo<serde_cw_value::Value> = from_json(val)?;
+
+    Ok(Cw721RawTokenInfo {
+        owner: info.owner.into_string(),
+        approvals: info.approvals.into_iter().map(|a| Approval {
+            spender: a.spender.into_string(),
+            expires: a.expires
+        }).collect(),
+        token_uri: info.token_uri,
+        extension: to_json_binary(&info.extension)?,
+    })
+}
+
+
+/// Loops up to `loop_limit` times to get token_ids, since max limit is 100 in cw721 base
+/// - `max_limit` is the page size of each query, defaults to 100
+pub fn cw721_bundle_query_ids(
+    deps: Deps,
+    // Number of loops to complete
+    // 1 loop = 100 token_ids to query
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    contract: String,
+    start_after: Option<String>
+) -> StdResult<Binary> {
+
+    let page = query_cw721_all_tokens(deps, loop_limit, max_limit, &contract, start_after)?;
+
+    to_json_binary(&page)
+
+}
+
+/// Loops up to `loop_limit` times over `Cw721QueryMsg::AllTokens`, returning all token_ids found
+fn query_cw721_all_tokens(
+    deps: Deps,
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    contract: &str,
+    start_after: Option<String>
+) -> StdResult<Page<String>> {
+
+    let limit = validate_page_limit(max_limit, CW721_MAX_LIMIT)?;
+
+    let mut budget = loop_limit;
+
+    query_pages::<TokensResponse>(
+        deps,
+        contract,
+        &mut budget,
+        limit,
+        start_after,
+        |start_after| to_json_binary(&cw721::Cw721QueryMsg::AllTokens { start_after, limit: Some(limit) })
+    )
+}
+
+
+/// Smart Queries `Cw721QueryMsg::NftInfo` for the selected tokens and counts each
+/// `(trait_type, value)` found in the `attributes` of their `cw721-metadata-onchain` [Extension]
+/// - Token_ids whose query fails are returned in `errors` instead of failing the call
+/// - The returned [TraitHistogram] can be merged with those of other batches
+pub fn cw721_bundle_query_trait_histogram(
+    deps: Deps,
+    selection: Cw721TokenSelection,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    let (token_ids, next_start_after, exhausted) = match selection {
+        Cw721TokenSelection::TokenIds(token_ids) => (token_ids, None, true),
+        Cw721TokenSelection::AllTokens { loop_limit, max_limit, start_after } => {
+            let page = query_cw721_all_tokens(deps, loop_limit, max_limit, &contract, start_after)?;
+            (page.items, page.next_start_after, page.exhausted)
+        }
+    };
+
+    let mut traits: BTreeMap<(String, String), u64> = BTreeMap::new();
+    let mut errors: Vec<(String, String)> = vec![];
+
+    for id in token_ids.iter() {
+
+        // The entire call will fail if base64 serialization fails for -any- Query Msg
+        let msg = to_json_binary(&cw721::Cw721QueryMsg::NftInfo {
+            token_id: id.clone()
+        })?;
+
+        let response: StdResult<NftInfoResponse<Extension>> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.clone(),
+            msg
+        }));
+
+        match response {
+            Err(e) => errors.push((id.to_owned(), e.to_msg())),
+            Ok(val) => {
+                let attributes = val.extension.and_then(|meta| meta.attributes).unwrap_or_default();
+                for attr in attributes.into_iter() {
+                    *traits.entry((attr.trait_type, attr.value)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    to_json_binary(&TraitHistogram {
+        token_count: (token_ids.len() - errors.len()) as u64,
+        traits: traits.into_iter().map(|((trait_type, value), count)| (trait_type, value, count)).collect(),
+        errors,
+        next_start_after,
+        exhausted
+    })
+}
+
+
+/// Raw Queries the `tokens` entry of each `(collection, token_id)` pair and checks it against `owner`
+/// - `layout` applies to every collection, defaults to [Cw721Layout::Cw721BaseV17]
+/// - Invalid collection addresses and Contract Errors are returned as errors for that pair
+pub fn cw721_bundle_verify_ownership(
+    deps: Deps,
+    owner: String,
+    tokens: Vec<(String, String)>,
+    layout: Option<Cw721Layout>
+) -> StdResult<Binary> {
+
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let layout = layout.unwrap_or_default();
+
+    let mut res: Vec<(String, String, Cw721OwnershipRes)> = Vec::with_capacity(tokens.len());
+
+    for (collection, id) in tokens.into_iter() {
+
+        if let Err(e) = deps.api.addr_validate(&collection) {
+            res.push((collection, id, Cw721OwnershipRes::error(e.to_msg())));
+            continue;
+        }
+
+        let response = match query_cw721_token_info_raw(deps, &collection, &id, &layout)? {
+            BundleReturn::Error(e) => Cw721OwnershipRes::error(e),
+            BundleReturn::Success(None) => Cw721OwnershipRes::success(Cw721Ownership::Missing),
+            BundleReturn::Success(Some(info)) if info.owner == owner.as_str() => {
+                Cw721OwnershipRes::success(Cw721Ownership::Owned)
+            },
+            BundleReturn::Success(Some(_)) => Cw721OwnershipRes::success(Cw721Ownership::NotOwned)
+        };
+
+        res.push((collection, id, response));
+    }
+
+    let all_owned = res.iter().all(|(_, _, status)| {
+        *status == Cw721OwnershipRes::Success(Cw721Ownership::Owned)
+    });
+
+    to_json_binary(&Cw721OwnershipVerification {
+        all_owned,
+        tokens: res
+    })
+}
+
+
+/// Smart Queries each `(contract, start_after)` with `Cw721QueryMsg::Tokens` for a single `owner`
+/// - `loop_limit` is the number of queries shared across all contracts,
+///   contracts that are not reached before it runs out are returned with their cursor unchanged
+/// - `max_limit` is the page size of each query, defaults to 100
+/// - Invalid contract addresses and Query Errors are returned as errors for that contract
+pub fn cw721_bundle_query_portfolio(
+    deps: Deps,
+    owner: String,
+    contracts: Vec<(String, Option<String>)>,
+    loop_limit: u32,
+    max_limit: Option<u32>
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&owner)?;
+
+    let limit = validate_page_limit(max_limit, CW721_MAX_LIMIT)?;
+
+    let mut budget = loop_limit;
+
+    let mut res: Vec<(String, Cw721PortfolioRes)> = Vec::with_capacity(contracts.len());
+
+    for (contract, start_after) in contracts.into_iter() {
+
+        if let Err(e) = deps.api.addr_validate(&contract) {
+            res.push((contract, Cw721PortfolioRes::error(e.to_msg())));
+            continue;
+        }
+
+        let response = query_pages::<TokensResponse>(
+            deps,
+            &contract,
+            &mut budget,
+            limit,
+            start_after,
+            |start_after| to_json_binary(&cw721::Cw721QueryMsg::Tokens { owner: owner.clone(), start_after, limit: Some(limit) })
+        );
+
+        match response {
+            Err(e) => res.push((contract, Cw721PortfolioRes::error(e.to_msg()))),
+            Ok(page) => res.push((contract, Cw721PortfolioRes::success(page)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+
+// ----------------------------------------------------------------- CW-2981
+
+/// Smart Queries the provided contract with `Cw2981QueryMsg::RoyaltyInfo`
+/// - The message is wrapped in `Cw721QueryMsg::Extension` as done by
+///   [cw2981-royalties](https://github.com/CosmWasm/cw-nfts/tree/main/contracts/cw2981-royalties)
+/// - Collections that do not implement cw2981 return an error for each token_id
+pub fn cw2981_bundle_query_royalties(
+    deps: Deps,
+    token_ids: Vec<String>,
+    sale_price: Uint128,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    let mut res: Vec<(String, Cw2981QueryResSmart)> = Vec::with_capacity(token_ids.len());
+
+    for id in token_ids.iter() {
+
+        // The entire call will fail if base64 serialization fails for -any- Query Msg
+        let msg = to_json_binary(&cw2981_royalties::QueryMsg::Extension {
+            msg: Cw2981QueryMsg::RoyaltyInfo {
+                token_id: id.clone(),
+                sale_price
+            }
+        })?;
+
+        // I do not propagate Query Errors, instead handle them individually
+        // so that successful Query Responses can still be returned
+        let response: StdResult<RoyaltiesInfoResponse> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.clone(),
+            msg
+        }));
+
+        match response {
+            Err(e) => res.push((id.to_owned(), Cw2981QueryResSmart::error(e.to_msg()))),
+            Ok(val) => res.push((id.to_owned(), Cw2981QueryResSmart::success(val)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+
+// ----------------------------------------------------------------- CW-20
+
+/// Smart Queries the provided contract with `Cw20QueryMsg::Balance`
+pub fn cw20_balances_bundle_query_smart(
+    deps: Deps,
+    accounts: Vec<String>,
+    contract: String
+) -> StdResult<Binary> {
+    
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    // Vec<(account, Cw20QueryResSmart)>
+    let mut res: Vec<(String, Cw20QueryResSmart)> = Vec::with_capacity(accounts.len());
+
+    for account in accounts.iter() {
+
+        // The entire call will fail if base64 serialization fails for -any- Query Msg
+        let msg = to_json_binary(&cw20::Cw20QueryMsg::Balance { 
+            address: account.clone() 
+        })?;
+
+        // I do not propogate Query Errors, instead handle them individually
+        // so that successful Query Responses can still be returned
+        let response: StdResult<BalanceResponse> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.clone(),
+            msg
+        }));
+
+        match response {
+            Err(e) => res.push((account.to_owned(), Cw20QueryResSmart::error(e.to_msg()))),
+            Ok(val) => res.push((account.to_owned(), Cw20QueryResSmart::success(val)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+/// Raw Queries the provided contract and accounts
+pub fn cw20_balances_bundle_query_raw(
+    deps: Deps,
+    accounts: Vec<String>,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _validate = deps.api.addr_validate(contract.as_str())?;
+
+    let mut res: Vec<(String, QueryResRaw)> = Vec::with_capacity(accounts.len());
+
+    for account in accounts.iter() {
+
+        // NOTE: Primary Key of cw20-base Balances is &Addr, but I skip
+        // address validation of each account to minimize query gas usage
+        // You may want to add that here depending on your preference
+         
+        // If key encoding fails for any account, the entire call fails
+        let key = account.to_raw_map_key("balance")?;
+
+        // Entire call only fails if request serialization fails for any account,
+        // or if raw_query results in a System Error
+        let response = to_query_res_raw(query_raw_key(deps, &contract, key)?);
+
+        res.push((account.to_owned(), response));
+    }
+
+    to_json_binary(&res)
+}
+
+/// Queries the balance of a single `account` across many cw20 contracts
+/// - `mode` selects between `Cw20QueryMsg::Balance` and raw reads of the cw20-base `balance` map
+/// - Invalid contract addresses and Query Errors are returned as errors for that contract
+pub fn cw20_bundle_query_portfolio(
+    deps: Deps,
+    account: String,
+    contracts: Vec<String>,
+    mode: QueryMode
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&account)?;
+
+    let mut res: Vec<(String, BalanceRes)> = Vec::with_capacity(contracts.len());
+
+    for contract in contracts.into_iter() {
+
+        if let Err(e) = deps.api.addr_validate(&contract) {
+            res.push((contract, BalanceRes::error(e.to_msg())));
+            continue;
+        }
+
+        let response = query_cw20_balance(deps, &contract, &account, &mode)?;
+
+        res.push((contract, response));
+    }
+
+    to_json_binary(&res)
+}
+
+/// Queries the balance of `account` on a cw20 contract
+/// - In [QueryMode::Raw], a missing `balance` entry is returned as zero, the same as cw20-base does
+/// - Only returns `Err` if key encoding or request serialization fails, or on a System Error
+fn query_cw20_balance(
+    deps: Deps,
+    contract: &str,
+    account: &str,
+    mode: &QueryMode
+) -> StdResult<BalanceRes> {
+
+    let response = match mode {
+        QueryMode::Smart => {
+            match query_smart::<BalanceResponse>(deps, contract, &cw20::Cw20QueryMsg::Balance {
+                address: account.to_string()
+            })? {
+                BundleReturn::Error(e) => BalanceRes::error(e),
+                BundleReturn::Success(val) => BalanceRes::success(val.balance)
+            }
+        },
+        QueryMode::Raw => {
+            let key = account.to_string().to_raw_map_key("balance")?;
+
+            match decode_raw::<Uint128>(query_raw_key(deps, contract, key)?) {
+                BundleReturn::Error(e) => BalanceRes::error(e),
+                BundleReturn::Success(val) => BalanceRes::success(val.unwrap_or_default())
+            }
+        }
+    };
+
+    Ok(response)
+}
+
+/// Raw Queries the `balance` of an account returned by `Cw20QueryMsg::AllAccounts`
+/// - Every enumerated account has a `balance` entry, so unlike [query_cw20_balance] a missing entry
+///   is returned as an error, as it means the contract does not use the cw20-base layout
+/// - Only returns `Err` if key encoding or request serialization fails, or on a System Error
+fn query_cw20_holder_balance(
+    deps: Deps,
+    contract: &str,
+    account: &str
+) -> StdResult<BalanceRes> {
+
+    let key = account.to_string().to_raw_map_key("balance")?;
+
+    let response = match decode_raw::<Uint128>(query_raw_key(deps, contract, key)?) {
+        BundleReturn::Error(e) => BalanceRes::error(e),
+        BundleReturn::Success(None) => BalanceRes::error("Nonexistent key"),
+        BundleReturn::Success(Some(val)) => BalanceRes::success(val)
+    };
+
+    Ok(response)
+}
+
+/// Smart Queries the provided contract with `Cw20QueryMsg::Allowance` for each `(owner, spender)`
+/// - `expired` is checked against the current block
+pub fn cw20_allowances_bundle_query_smart(
+    deps: Deps,
+    block: &BlockInfo,
+    allowances: Vec<(String, String)>,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    // Vec<(owner, spender, Cw20AllowanceRes)>
+    let mut res: Vec<(String, String, Cw20AllowanceRes)> = Vec::with_capacity(allowances.len());
+
+    for (owner, spender) in allowances.into_iter() {
+
+        // The entire call will fail if base64 serialization fails for -any- Query Msg
+        let msg = to_json_binary(&cw20::Cw20QueryMsg::Allowance {
+            owner: owner.clone(),
+            spender: spender.clone()
+        })?;
+
+        // I do not propagate Query Errors, instead handle them individually
+        // so that successful Query Responses can still be returned
+        let response: StdResult<AllowanceResponse> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract.clone(),
+            msg
+        }));
+
+        match response {
+            Err(e) => res.push((owner, spender, Cw20AllowanceRes::error(e.to_msg()))),
+            Ok(val) => res.push((owner, spender, Cw20AllowanceRes::success(Cw20AllowanceInfo::new(val, block))))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+/// Raw Queries the cw20-base `allowance` map of the provided contract for each `(owner, spender)`
+/// - `expired` is checked against the current block
+/// - An allowance that was never set is returned as zero and never expiring, the same as cw20-base does
+pub fn cw20_allowances_bundle_query_raw(
+    deps: Deps,
+    block: &BlockInfo,
+    allowances: Vec<(String, String)>,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _validate = deps.api.addr_validate(contract.as_str())?;
+
+    let mut res: Vec<(String, String, Cw20AllowanceRes)> = Vec::with_capacity(allowances.len());
+
+    for (owner, spender) in allowances.into_iter() {
+
+        // If key encoding fails for any (owner, spender), the entire call fails
+        let key = (owner.clone(), spender.clone()).to_raw_map_key("allowance")?;
+
+        // Entire call only fails if request serialization fails for any (owner, spender),
+        // or if raw_query results in a System Error
+        let response = match decode_raw::<AllowanceResponse>(query_raw_key(deps, &contract, key)?) {
+            BundleReturn::Error(e) => Cw20AllowanceRes::error(e),
+            BundleReturn::Success(val) => Cw20AllowanceRes::success(Cw20AllowanceInfo::new(val.unwrap_or_default(), block))
+        };
+
+        res.push((owner, spender, response));
+    }
+
+    to_json_binary(&res)
+}
+
+/// Loops over `Cw20QueryMsg::AllAllowances` for each `(owner, start_after)`
+/// - `loop_limit` is the number of queries shared across all owners,
+///   owners that are not reached before it runs out are returned with their cursor unchanged
+/// - `max_limit` is the page size of each query, defaults to 30
+pub fn cw20_bundle_query_all_allowances(
+    deps: Deps,
+    owners: Vec<(String, Option<String>)>,
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    let limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
+
+    let mut budget = loop_limit;
+
+    let mut res: Vec<(String, BundleReturn<Page<AllowanceInfo>>)> = Vec::with_capacity(owners.len());
+
+    for (owner, start_after) in owners.into_iter() {
+
+        let response = query_pages::<AllAllowancesResponse>(
+            deps,
+            &contract,
+            &mut budget,
+            limit,
+            start_after,
+            |start_after| to_json_binary(&cw20::Cw20QueryMsg::AllAllowances { owner: owner.clone(), start_after, limit: Some(limit) })
+        );
+
+        match response {
+            Err(e) => res.push((owner, BundleReturn::error(e.to_msg()))),
+            Ok(page) => res.push((owner, BundleReturn::success(page)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+/// Loops over `Cw20QueryMsg::AllSpenderAllowances` for each `(spender, start_after)`
+/// - `loop_limit` is the number of queries shared across all spenders,
+///   spenders that are not reached before it runs out are returned with their cursor unchanged
+/// - `max_limit` is the page size of each query, defaults to 30
+pub fn cw20_bundle_query_all_spender_allowances(
+    deps: Deps,
+    spenders: Vec<(String, Option<String>)>,
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    contract: String
+) -> StdResult<Binary> {
+
+    let _valid = deps.api.addr_validate(&contract)?;
+
+    let limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
+
+    let mut budget = loop_limit;
+
+    let mut res: Vec<(String, BundleReturn<Page<SpenderAllowanceInfo>>)> = Vec::with_capacity(spenders.len());
+
+    for (spender, start_after) in spenders.into_iter() {
+
+        let response = query_pages::<AllSpenderAllowancesResponse>(
+            deps,
+            &contract,
+            &mut budget,
+            limit,
+            start_after,
+            |start_after| to_json_binary(&Cw20SpenderQueryMsg::AllSpenderAllowances { spender: spender.clone(), start_after, limit: Some(limit) })
+        );
+
+        match response {
+            Err(e) => res.push((spender, BundleReturn::error(e.to_msg()))),
+            Ok(page) => res.push((spender, BundleReturn::success(page)))
+        }
+    }
+
+    to_json_binary(&res)
+}
+
+/// Queries `TokenInfo`, `Minter`, `MarketingInfo` and the cw2 version of each cw20 contract
+/// - Reads the cw20-base `token_info` and `marketing_info` Items and the cw2 `contract_info` Item
+/// - If `token_info` cannot be read the layout is unknown, so `TokenInfo`, `Minter`
+///   and `MarketingInfo` are Smart Queried instead
+/// - Errors are returned per field, invalid contract addresses are returned as errors
+pub fn cw20_bundle_query_metadata(
+    deps: Deps,
+    contracts: Vec<String>
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, Cw20MetadataRes)> = Vec::with_capacity(contracts.len());
+
+    for contract in contracts.into_iter() {
+
+        if let Err(e) = deps.api.addr_validate(&contract) {
+            res.push((contract, Cw20MetadataRes::error(e.to_msg())));
+            continue;
+        }
+
+        let token_info = decode_raw::<Cw20StoredTokenInfo>(
+            query_raw_key(deps, &contract, Binary::from(b"token_info"))?
+        );
+
+        let (token_info, minter, marketing_info) = match token_info {
+            BundleReturn::Success(Some(info)) => {
+                let marketing_info = query_cw20_marketing_info_raw(deps, &contract)?;
+
+                (
+                    BundleReturn::success(TokenInfoResponse {
+                        name: info.name,
+                        symbol: info.symbol,
+                        decimals: info.decimals,
+                        total_supply: info.total_supply,
+                    }),
+                    BundleReturn::success(info.mint.map(|m| MinterResponse {
+                        minter: m.minter.into_string(),
+                        cap: m.cap,
+                    })),
+                    marketing_info
+                )
+            },
+            BundleReturn::Success(None) | BundleReturn::Error(_) => (
+                query_smart(deps, &contract, &cw20::Cw20QueryMsg::TokenInfo {})?,
+                query_smart(deps, &contract, &cw20::Cw20QueryMsg::Minter {})?,
+                query_smart(deps, &contract, &cw20::Cw20QueryMsg::MarketingInfo {})?
+            )
+        };
+
+        let version = match decode_raw::<ContractVersion>(
+            query_raw_key(deps, &contract, Binary::from(b"contract_info"))?
+        ) {
+            BundleReturn::Error(e) => BundleReturn::error(e),
+            BundleReturn::Success(None) => BundleReturn::error("Nonexistent key"),
+            BundleReturn::Success(Some(val)) => BundleReturn::success(val)
+        };
+
+        res.push((contract, Cw20MetadataRes::success(Cw20Metadata {
+            token_info,
+            minter,
+            marketing_info,
+            version
+        })));
+    }
+
+    to_json_binary(&res)
+}
+
+/// Raw Queries the cw20-base `marketing_info` and `logo` Items of each cw20 contract
+/// - Embedded logos are returned with their MIME type, the same as `Cw20QueryMsg::DownloadLogo`
+/// - Embedded logos larger than `max_logo_size` bytes are skipped, only their size is returned
+/// - Errors are returned per field, invalid contract addresses are returned as errors
+pub fn cw20_bundle_query_marketing(
+    deps: Deps,
+    contracts: Vec<String>,
+    max_logo_size: u32
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, Cw20MarketingRes)> = Vec::with_capacity(contracts.len());
+
+    for contract in contracts.into_iter() {
+
+        if let Err(e) = deps.api.addr_validate(&contract) {
+            res.push((contract, Cw20MarketingRes::error(e.to_msg())));
+            continue;
+        }
+
+        let marketing_info = query_cw20_marketing_info_raw(deps, &contract)?;
+
+        let logo = match decode_raw::<Logo>(query_raw_key(deps, &contract, Binary::from(b"logo"))?) {
+            BundleReturn::Error(e) => BundleReturn::error(e),
+            BundleReturn::Success(val) => BundleReturn::success(val.map(|logo| {
+                let (mime_type, data) = match logo {
+                    Logo::Url(url) => return Cw20LogoData::Url(url),
+                    Logo::Embedded(EmbeddedLogo::Svg(data)) => ("image/svg+xml", data),
+                    Logo::Embedded(EmbeddedLogo::Png(data)) => ("image/png", data),
+                };
+
+                if data.len() > max_logo_size as usize {
+                    Cw20LogoData::Skipped { mime_type: mime_type.to_string(), size: data.len() as u64 }
+                } else {
+                    Cw20LogoData::Embedded { mime_type: mime_type.to_string(), data }
+                }
+            }))
+        };
 
-    pub fn error(msg: impl Into<String>) -> Self {
-        Self::Error(msg.into())
+        res.push((contract, Cw20MarketingRes::success(Cw20Marketing {
+            marketing_info,
+            logo
+        })));
     }
+
+    to_json_binary(&res)
 }
 
-// ------------------------------------------------------------------------
-// ------------------------------------------------------------ Query Logic
-// ------------------------------------------------------------------------
+/// Raw Queries the cw20-base `marketing_info` Item, which is only saved once marketing info is set
+fn query_cw20_marketing_info_raw(
+    deps: Deps,
+    contract: &str
+) -> StdResult<BundleReturn<MarketingInfoResponse>> {
+
+    let response = match decode_raw::<MarketingInfoResponse>(
+        query_raw_key(deps, contract, Binary::from(b"marketing_info"))?
+    ) {
+        BundleReturn::Error(e) => BundleReturn::error(e),
+        BundleReturn::Success(val) => BundleReturn::success(val.unwrap_or_default())
+    };
 
-// ----------------------------------------------------------------- CW-721
+    Ok(response)
+}
 
-/// Smart Queries the provided contract with `Cw721QueryMsg::NftInfo`
-/// - This function requires deserializing successful query responses into a provided type
-/// - The type used here is [Extension](https://github.com/CosmWasm/cw-nfts/blob/f8600e6a760ce6ad340ce286262c55f471b2fb70/contracts/cw721-metadata-onchain/src/lib.rs#L31)
-///   from the default `cw721-metadata-onchain` contract, but could be replaced with your own type
-pub fn cw721_bundle_query_smart(
+/// Loops up to `loop_limit` times to get accounts that have balances
+/// - `max_limit` is the page size of each query, defaults to 30
+pub fn cw20_bundle_query_accounts(
     deps: Deps,
-    token_ids: Vec<String>,
-    contract: String
+    // Number of loops to complete
+    // 1 loop = 30 accounts
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    contract: String,
+    start_after: Option<String>
 ) -> StdResult<Binary> {
-    
-    let _valid = deps.api.addr_validate(&contract)?;
 
-    let mut res: Vec<(String, Cw721QueryResSmart)> = Vec::with_capacity(token_ids.len());
+    let limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
 
-    for id in token_ids.iter() {
+    let mut budget = loop_limit;
 
-        // The entire call will fail if base64 serialization fails for -any- Query Msg
-        let msg = to_binary(&cw721::Cw721QueryMsg::NftInfo {
-            token_id: id.clone()
-        })?;
+    let page = query_pages::<AllAccountsResponse>(
+        deps,
+        &contract,
+        &mut budget,
+        limit,
+        start_after,
+        |start_after| to_json_binary(&cw20::Cw20QueryMsg::AllAccounts { start_after, limit: Some(limit) })
+    )?;
 
-        // I do not propogate Query Errors, instead handle them individually
-        // so that successful Query Responses can still be returned
-        let response: StdResult<NftInfoResponse<Extension>> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: contract.clone(),
-            msg
-        }));
+    to_json_binary(&page)
 
-        match response {
-            Err(e) => res.push((id.to_owned(), Cw721QueryResSmart::error(e.to_msg()))),
-            Ok(val) => res.push((id.to_owned(), Cw721QueryResSmart::success(val)))
+}
+
+/// Pages through `Cw20QueryMsg::AllAccounts` and Raw Queries the `balance` of each account found
+/// - A missing `balance` entry is returned as an error for that account rather than as zero
+/// - Stops once `max_items` accounts have been returned or every account has been found
+/// - `max_limit` is the page size of each query, defaults to 30
+pub fn cw20_bundle_query_holder_snapshot(
+    deps: Deps,
+    max_items: u32,
+    max_limit: Option<u32>,
+    contract: String,
+    start_after: Option<String>
+) -> StdResult<Binary> {
+
+    let _validate = deps.api.addr_validate(contract.as_str())?;
+
+    let max_limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
+
+    let mut snapshot: Page<(String, BalanceRes)> = Page::new(start_after);
+
+    while (snapshot.items.len() as u32) < max_items {
+
+        // Never request more accounts than are left in the budget
+        let limit = (max_items - snapshot.items.len() as u32).min(max_limit);
+
+        // One page at a time, so balances are only queried for accounts within the budget
+        let mut budget = 1;
+        let page = query_pages::<AllAccountsResponse>(
+            deps,
+            &contract,
+            &mut budget,
+            limit,
+            snapshot.next_start_after.clone(),
+            |start_after| to_json_binary(&cw20::Cw20QueryMsg::AllAccounts { start_after, limit: Some(limit) })
+        )?;
+
+        snapshot.next_start_after = page.next_start_after;
+
+        for account in page.items.into_iter() {
+            let balance = query_cw20_holder_balance(deps, &contract, &account)?;
+            snapshot.items.push((account, balance));
+        }
+
+        if page.exhausted {
+            snapshot.exhausted = true;
+            break;
         }
     }
 
-    to_binary(&res)
+    to_json_binary(&snapshot)
 }
 
-
-/// Raw Queries the provided contract and token_id's
-pub fn cw721_bundle_query_raw(
+/// Pages through `Cw20QueryMsg::AllAccounts` and aggregates the raw `balance` of each account found
+/// - `top_n` is the number of largest holders to return
+/// - `bucket_bounds` are the ascending lower bounds of the balance histogram, a bucket from
+///   zero is always included
+/// - Accounts whose balance cannot be read, including a missing `balance` entry, are returned in `errors`
+/// - The returned [Cw20HolderStats] can be merged with those of other windows
+pub fn cw20_bundle_query_holder_stats(
     deps: Deps,
-    token_ids: Vec<String>,
-    contract: String
+    loop_limit: u32,
+    max_limit: Option<u32>,
+    top_n: u32,
+    bucket_bounds: Vec<Uint128>,
+    contract: String,
+    start_after: Option<String>
 ) -> StdResult<Binary> {
 
     let _validate = deps.api.addr_validate(contract.as_str())?;
 
-    let mut res: Vec<(String, QueryResRaw)> = Vec::with_capacity(token_ids.len());
+    let limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
 
-    for id in token_ids.iter() {
-        
-        // If key encoding fails for any token_id, the entire call fails
-        let key = id.to_raw_map_key("tokens")?;
+    if bucket_bounds.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(StdError::generic_err("Bucket bounds must be strictly ascending"));
+    }
 
-        let request: QueryRequest<Empty> = WasmQuery::Raw {
-            contract_addr: contract.clone(),
-            key
-        }.into();
-    
-        // If query request serialization fails for any token_id, the entire call fails
-        let raw = cosmwasm_std::to_vec(&request).map_err(|serialize_err| {
-            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
-        })?;
+    let mut budget = loop_limit;
+
+    let page = query_pages::<AllAccountsResponse>(
+        deps,
+        &contract,
+        &mut budget,
+        limit,
+        start_after,
+        |start_after| to_json_binary(&cw20::Cw20QueryMsg::AllAccounts { start_after, limit: Some(limit) })
+    )?;
+
+    let mut stats = Cw20HolderStats {
+        holder_count: 0,
+        non_zero_count: 0,
+        total_balance: Uint128::zero(),
+        top_n,
+        top_holders: vec![],
+        buckets: std::iter::once(Uint128::zero())
+            .chain(bucket_bounds.into_iter().filter(|bound| !bound.is_zero()))
+            .map(|bound| (bound, 0))
+            .collect(),
+        errors: vec![],
+        next_start_after: page.next_start_after,
+        exhausted: page.exhausted
+    };
 
-        // Entire call only fails if raw_query results in aSystem Error,
-        // If query results in Contract Error or Success, value is returned
-        let response = deps.querier.raw_query(&raw)
-            .into_result()
-            .map_err(|e| StdError::generic_err(format!("System Err: {}", e)))
-            .and_then(|val| match val {
-                ContractResult::Err(err) => Ok(QueryResRaw::error(err)),
-                ContractResult::Ok(val) => {
-                    // Handle null byte, which means key did not exist
-                    if val.len() == 0 {
-                        return Ok(QueryResRaw::error("Nonexistent key"));
-                    } else {
-                        return Ok(QueryResRaw::success(val.to_base64()));
-                    }
+    for account in page.items.into_iter() {
+        match query_cw20_holder_balance(deps, &contract, &account)? {
+            BundleReturn::Error(e) => stats.errors.push((account, e)),
+            BundleReturn::Success(balance) => {
+                stats.holder_count += 1;
+                stats.total_balance = stats.total_balance.checked_add(balance)?;
+
+                if !balance.is_zero() {
+                    stats.non_zero_count += 1;
                 }
-            })?;
 
-        res.push((id.to_owned(), response));
+                // Buckets are ascending, so the balance belongs to the last one it reaches
+                if let Some(bucket) = stats.buckets.iter_mut().rev().find(|(bound, _)| balance >= *bound) {
+                    bucket.1 += 1;
+                }
+
+                stats.top_holders.push((account, balance));
+            }
+        }
     }
 
-    to_binary(&res)
-}
+    stats.sort_top_holders();
 
+    to_json_binary(&stats)
+}
 
-/// Loops `loop_limit` times to get token_ids, since max limit is 100 in cw721 base
-pub fn cw721_bundle_query_ids(
+/// Pages through `Cw20QueryMsg::AllAccounts` and adds the raw `balance` of each account to a running total
+/// - `cursor` carries the running total between calls, `None` starts from the first account
+/// - Once every account has been read, `reconciled` reports whether the total equals `TokenInfo.total_supply`
+/// - Any balance that cannot be read fails the call, including a missing `balance` entry,
+///   since the total would be meaningless
+pub fn cw20_bundle_query_supply_audit(
     deps: Deps,
-    // Number of loops to complete
-    // 1 loop = 100 token_ids to query
     loop_limit: u32,
     max_limit: Option<u32>,
     contract: String,
-    start_after: Option<String>
+    cursor: Option<Cw20AuditCursor>
 ) -> StdResult<Binary> {
 
-    let mut token_ids: Vec<String> = vec![];
+    let _validate = deps.api.addr_validate(contract.as_str())?;
 
-    let mut count = 0_u32;
+    let limit = validate_page_limit(max_limit, CW20_MAX_LIMIT)?;
 
-    let mut start_after: Option<String> = start_after;
+    let mut cursor = cursor.unwrap_or_default();
 
-    while count < loop_limit {
+    let mut budget = loop_limit;
 
-        let msg = to_binary(&cw721::Cw721QueryMsg::AllTokens {
-            start_after,
-            // Default limit is 10, max limit is 100
-            limit: max_limit.or(Some(100u32)),
-        })?;
+    let page = query_pages::<AllAccountsResponse>(
+        deps,
+        &contract,
+        &mut budget,
+        limit,
+        cursor.start_after.clone(),
+        |start_after| to_json_binary(&cw20::Cw20QueryMsg::AllAccounts { start_after, limit: Some(limit) })
+    )?;
 
-        let response: TokensResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: contract.clone(),
-            msg
-        }))?;
-        
-        // Update start_after for next query
-        start_after = response.tokens.last().cloned();
+    let accounts_read = page.items.len() as u64;
 
-        // If response length is less than 100 or max_limit, set count to loop_limit to stop firing queries
-        if response.tokens.len() < max_limit.unwrap_or_else(|| 100).try_into().map_err(|_e| StdError::GenericErr { msg: "invalid max limit".to_string() })? {
-            count = loop_limit;
-        } else {
-            count += 1;
+    for account in page.items.into_iter() {
+        match query_cw20_holder_balance(deps, &contract, &account)? {
+            BundleReturn::Error(e) => {
+                return Err(StdError::generic_err(format!("Balance of {account}: {e}")));
+            },
+            BundleReturn::Success(balance) => {
+                cursor.running_total = cursor.running_total.checked_add(balance)?;
+            }
         }
+    }
 
-        // Add token_ids from this query
-        token_ids.extend(response.tokens);
-    };
+    cursor.start_after = page.next_start_after;
 
-    to_binary(&token_ids)
+    let token_info: TokenInfoResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: contract.clone(),
+        msg: to_json_binary(&cw20::Cw20QueryMsg::TokenInfo {})?
+    }))?;
 
+    to_json_binary(&Cw20SupplyAudit {
+        accounts_read,
+        reconciled: page.exhausted.then_some(cursor.running_total == token_info.total_supply),
+        total_supply: token_info.total_supply,
+        exhausted: page.exhausted,
+        cursor
+    })
 }
 
 
-// ----------------------------------------------------------------- CW-20
+// ----------------------------------------------------------------- Assets
 
-/// Smart Queries the provided contract with `Cw20QueryMsg::Balance`
-pub fn cw20_balances_bundle_query_smart(
+/// Queries the balance of each account for each native or cw20 asset
+/// - Native balances use `BankQuery::Balance`, cw20 balances use `mode`
+/// - Errors are returned per balance, invalid cw20 addresses are returned as errors for that asset
+pub fn asset_balances_bundle_query(
     deps: Deps,
     accounts: Vec<String>,
-    contract: String
+    assets: Vec<AssetInfo>,
+    mode: QueryMode
 ) -> StdResult<Binary> {
-    
-    let _valid = deps.api.addr_validate(&contract)?;
 
-    // Vec<(account, Cw20QueryResSmart)>
-    let mut res: Vec<(String, Cw20QueryResSmart)> = Vec::with_capacity(accounts.len());
+    // Validate each cw20 address once, rather than once per account
+    let invalid: Vec<Option<String>> = assets.iter().map(|asset| match asset {
+        AssetInfo::Native(_) => None,
+        AssetInfo::Cw20(contract) => deps.api.addr_validate(contract).err().map(|e| e.to_msg())
+    }).collect();
 
-    for account in accounts.iter() {
+    let mut balances: Vec<(String, Vec<BalanceRes>)> = Vec::with_capacity(accounts.len());
 
-        // The entire call will fail if base64 serialization fails for -any- Query Msg
-        let msg = to_binary(&cw20::Cw20QueryMsg::Balance { 
-            address: account.clone() 
-        })?;
+    for account in accounts.into_iter() {
 
-        // I do not propogate Query Errors, instead handle them individually
-        // so that successful Query Responses can still be returned
-        let response: StdResult<BalanceResponse> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: contract.clone(),
-            msg
+        let mut row: Vec<BalanceRes> = Vec::with_capacity(assets.len());
+
+        for (asset, invalid) in assets.iter().zip(invalid.iter()) {
+
+            let balance = match (asset, invalid) {
+                (_, Some(e)) => BalanceRes::error(e.clone()),
+                (AssetInfo::Native(denom), None) => match deps.querier.query_balance(&account, denom) {
+                    Err(e) => BalanceRes::error(e.to_msg()),
+                    Ok(coin) => BalanceRes::success(coin.amount)
+                },
+                (AssetInfo::Cw20(contract), None) => query_cw20_balance(deps, contract, &account, &mode)?
+            };
+
+            row.push(balance);
+        }
+
+        balances.push((account, row));
+    }
+
+    to_json_binary(&AssetBalanceMatrix {
+        assets,
+        balances
+    })
+}
+
+
+// ----------------------------------------------------------------- Bank
+
+/// Queries `BankQuery::Balance` for each address and denom
+/// - If `all_balances` is true, `BankQuery::AllBalances` is also queried for each address
+/// - Errors are returned per balance instead of failing the call
+pub fn bank_balances_bundle_query(
+    deps: Deps,
+    addresses: Vec<String>,
+    denoms: Vec<String>,
+    all_balances: bool
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, BankBalances)> = Vec::with_capacity(addresses.len());
+
+    for address in addresses.into_iter() {
+
+        let balances = denoms.iter().map(|denom| {
+            match deps.querier.query_balance(&address, denom) {
+                Err(e) => (denom.to_owned(), BalanceRes::error(e.to_msg())),
+                Ok(coin) => (denom.to_owned(), BalanceRes::success(coin.amount))
+            }
+        }).collect();
+
+        let all_balances = all_balances.then(|| {
+            match deps.querier.query_all_balances(&address) {
+                Err(e) => BundleReturn::error(e.to_msg()),
+                Ok(coins) => BundleReturn::success(coins)
+            }
+        });
+
+        res.push((address, BankBalances {
+            balances,
+            all_balances
         }));
+    }
 
-        match response {
-            Err(e) => res.push((account.to_owned(), Cw20QueryResSmart::error(e.to_msg()))),
-            Ok(val) => res.push((account.to_owned(), Cw20QueryResSmart::success(val)))
+    to_json_binary(&res)
+}
+
+/// Queries `BankQuery::Supply` for each denom
+/// - Requires the `cosmwasm_1_1` capability, so the contract cannot be stored on chains without the query
+/// - Errors from the query, such as an unknown denom, are returned per denom instead of failing the call
+#[cfg(feature = "cosmwasm_1_1")]
+pub fn bank_supply_bundle_query(
+    deps: Deps,
+    denoms: Vec<String>
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, BalanceRes)> = Vec::with_capacity(denoms.len());
+
+    for denom in denoms.into_iter() {
+
+        // query_supply returns System Errors as Query Errors, so they are handled per denom
+        match deps.querier.query_supply(&denom) {
+            Err(e) => res.push((denom, BalanceRes::error(e.to_msg()))),
+            Ok(coin) => res.push((denom, BalanceRes::success(coin.amount)))
         }
     }
 
-    to_binary(&res)
+    to_json_binary(&res)
 }
 
-/// Raw Queries the provided contract and accounts
-pub fn cw20_balances_bundle_query_raw(
+
+/// Queries `BankQuery::DenomMetadata` for each denom
+/// - Denoms without metadata return an error instead of failing the call
+#[cfg(feature = "cosmwasm_1_3")]
+pub fn bank_denom_metadata_bundle_query(
     deps: Deps,
-    accounts: Vec<String>,
-    contract: String
+    denoms: Vec<String>
 ) -> StdResult<Binary> {
 
-    let _validate = deps.api.addr_validate(contract.as_str())?;
+    let mut res: Vec<(String, BundleReturn<DenomMetadata>)> = Vec::with_capacity(denoms.len());
 
-    let mut res: Vec<(String, QueryResRaw)> = Vec::with_capacity(accounts.len());
+    for denom in denoms.into_iter() {
 
-    for account in accounts.iter() {
+        match deps.querier.query_denom_metadata(&denom) {
+            Err(e) => res.push((denom, BundleReturn::error(e.to_msg()))),
+            Ok(metadata) => res.push((denom, BundleReturn::success(metadata)))
+        }
+    }
 
-        // NOTE: Primary Key of cw20-base Balances is &Addr, but I skip
-        // address validation of each account to minimize query gas usage
-        // You may want to add that here depending on your preference
-         
-        // If key encoding fails for any account, the entire call fails
-        let key = account.to_raw_map_key("balance")?;
+    to_json_binary(&res)
+}
 
-        let request: QueryRequest<Empty> = WasmQuery::Raw {
-            contract_addr: contract.clone(),
-            key
-        }.into();
-    
-        // If query request serialization fails for any account, the entire call fails
-        let raw = cosmwasm_std::to_vec(&request).map_err(|serialize_err| {
-            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
-        })?;
 
-        // Entire call only fails if raw_query results in a System Error,
-        // If query results in Contract Error or Success, value is returned
-        let response = deps.querier.raw_query(&raw)
-            .into_result()
-            .map_err(|e| StdError::generic_err(format!("System Err: {}", e)))
-            .and_then(|val| match val {
-                ContractResult::Err(err) => Ok(QueryResRaw::error(err)),
-                ContractResult::Ok(val) => {
-                    // Handle null byte, which means key did not exist
-                    if val.len() == 0 {
-                        return Ok(QueryResRaw::error("Nonexistent key"));
-                    } else {
-                        return Ok(QueryResRaw::success(val.to_base64()));
-                    }
-                }
-            })?;
+// ----------------------------------------------------------------- Wasm
 
-        res.push((account.to_owned(), response));
+/// Queries `WasmQuery::ContractInfo` for each contract
+/// - With the `cosmwasm_1_2` feature, `WasmQuery::CodeInfo` is also queried once per distinct `code_id`
+/// - Errors are returned per contract instead of failing the call
+pub fn wasm_contract_info_bundle_query(
+    deps: Deps,
+    contracts: Vec<String>
+) -> StdResult<Binary> {
+
+    #[cfg(feature = "cosmwasm_1_2")]
+    let mut code_infos: BTreeMap<u64, BundleReturn<CodeInfoResponse>> = BTreeMap::new();
+
+    let mut res: Vec<(String, BundleReturn<WasmContractInfo>)> = Vec::with_capacity(contracts.len());
+
+    for contract in contracts.into_iter() {
+
+        let contract_info = match deps.querier.query_wasm_contract_info(&contract) {
+            Err(e) => {
+                res.push((contract, BundleReturn::error(e.to_msg())));
+                continue;
+            },
+            Ok(info) => info
+        };
+
+        #[cfg(feature = "cosmwasm_1_2")]
+        let code_info = code_infos
+            .entry(contract_info.code_id)
+            .or_insert_with(|| match deps.querier.query_wasm_code_info(contract_info.code_id) {
+                Err(e) => BundleReturn::error(e.to_msg()),
+                Ok(info) => BundleReturn::success(info)
+            })
+            .clone();
+
+        res.push((contract, BundleReturn::success(WasmContractInfo {
+            contract_info,
+            #[cfg(feature = "cosmwasm_1_2")]
+            code_info
+        })));
     }
 
-    to_binary(&res)
+    to_json_binary(&res)
 }
 
-/// Loops up to `loop_limit` times to get accounts that have balances
-pub fn cw20_bundle_query_accounts(
+
+/// Classifies each contract by reading the cw2 `contract_info` Item and probing the Items in [CLASSIFY_PROBES]
+/// - The cw2 contract name takes precedence over the probes only when it exactly matches a known base contract,
+///   so wrappers such as `dao-voting-cw721-staked` are left to the probes
+/// - Unlike the other raw queries, System Errors (such as a nonexistent contract) are returned per contract
+pub fn contract_bundle_query_classify(
     deps: Deps,
-    // Number of loops to complete
-    // 1 loop = 30 accounts
-    loop_limit: u32,
-    contract: String,
-    start_after: Option<String>
+    contracts: Vec<String>
 ) -> StdResult<Binary> {
 
-    let mut accounts: Vec<String> = vec![];
+    let mut res: Vec<(String, BundleReturn<ContractClassification>)> = Vec::with_capacity(contracts.len());
 
-    let mut count = 0_u32;
+    for contract in contracts.into_iter() {
+        match classify_contract(deps, &contract) {
+            Err(e) => res.push((contract, BundleReturn::error(e.to_msg()))),
+            Ok(classification) => res.push((contract, BundleReturn::success(classification)))
+        }
+    }
 
-    let mut start_after: Option<String> = start_after;
+    to_json_binary(&res)
+}
 
-    while count < loop_limit {
+/// Classifies a single contract for [contract_bundle_query_classify]
+fn classify_contract(
+    deps: Deps,
+    contract: &str
+) -> StdResult<ContractClassification> {
+
+    let contract_version = match decode_raw::<ContractVersion>(
+        query_raw_key(deps, contract, Binary::from(b"contract_info"))?
+    ) {
+        BundleReturn::Error(e) => return Err(StdError::generic_err(e)),
+        BundleReturn::Success(version) => version
+    };
 
-        let msg = to_binary(&cw20::Cw20QueryMsg::AllAccounts { 
-            start_after, 
-            limit: Some(30_u32)
-        })?;
+    let mut probes: Vec<String> = vec![];
+    let mut probed_standard: Option<ContractStandard> = None;
+
+    for (key, standard) in CLASSIFY_PROBES.into_iter() {
+        match query_raw_key(deps, contract, Binary::from(key.as_bytes()))? {
+            ContractResult::Err(e) => return Err(StdError::generic_err(e)),
+            // Handle null byte, which means key did not exist
+            ContractResult::Ok(val) if val.is_empty() => {},
+            ContractResult::Ok(_) => {
+                probes.push(key.to_string());
+                probed_standard.get_or_insert(standard);
+            }
+        }
+    }
 
-        let response: AllAccountsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: contract.clone(),
-            msg
-        }))?;
+    let named_standard = contract_version.as_ref().and_then(|version| {
+        let name = version.contract.to_lowercase();
+        match name.strip_prefix("crates.io:").unwrap_or(&name) {
+            "cw721-base" | "sg721-base" => Some(ContractStandard::Cw721),
+            "cw20-base" => Some(ContractStandard::Cw20),
+            "cw4-group" => Some(ContractStandard::Cw4Group),
+            "dao-dao-core" | "cw-core" => Some(ContractStandard::Dao),
+            _ => None
+        }
+    });
 
-        // Update start_after for next query
-        start_after = response.accounts.last().cloned();
+    Ok(ContractClassification {
+        standard: named_standard.or(probed_standard).unwrap_or(ContractStandard::Unknown),
+        contract_version,
+        probes
+    })
+}
+
+
+// ----------------------------------------------------------------- Staking
+
+/// Queries the delegations of each delegator
+/// - Without a `validator` every delegation is returned through `AllDelegations`
+/// - With a `validator` only that delegation is returned through `Delegation`,
+///   which includes `accumulated_rewards` and `can_redelegate`
+/// - Errors are returned per delegator instead of failing the call
+#[cfg(feature = "staking")]
+pub fn staking_delegations_bundle_query(
+    deps: Deps,
+    delegators: Vec<String>,
+    validator: Option<String>
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, BundleReturn<StakingDelegations>)> = Vec::with_capacity(delegators.len());
+
+    for delegator in delegators.into_iter() {
+
+        let delegations = match &validator {
+            None => deps.querier
+                .query_all_delegations(&delegator)
+                .map(StakingDelegations::All),
+            Some(validator) => deps.querier
+                .query_delegation(&delegator, validator)
+                .map(StakingDelegations::Validator)
+        };
 
-        // If response length is less than 30, set count to loop limit to stop firing queries
-        if response.accounts.len() < 30 {
-            count = loop_limit;
-        } else {
-            count += 1;
+        match delegations {
+            Err(e) => res.push((delegator, BundleReturn::error(e.to_msg()))),
+            Ok(delegations) => res.push((delegator, BundleReturn::success(delegations)))
         }
+    }
+
+    to_json_binary(&res)
+}
+
 
-        // Add accounts from this query
-        accounts.extend(response.accounts);
+/// Queries `BondedDenom`, `AllValidators` and `Validator` for each address in one call
+/// - `Validator` includes the commission rates and max change rate
+/// - Errors are returned per query and per validator instead of failing the call
+#[cfg(feature = "staking")]
+pub fn staking_validators_bundle_query(
+    deps: Deps,
+    validators: Vec<String>
+) -> StdResult<Binary> {
+
+    let bonded_denom = match deps.querier.query_bonded_denom() {
+        Err(e) => BundleReturn::error(e.to_msg()),
+        Ok(denom) => BundleReturn::success(denom)
+    };
 
+    let all_validators = match deps.querier.query_all_validators() {
+        Err(e) => BundleReturn::error(e.to_msg()),
+        Ok(all) => BundleReturn::success(all)
     };
 
-    to_binary(&accounts)
+    let validators = validators.into_iter().map(|address| {
+        match deps.querier.query_validator(&address) {
+            Err(e) => (address, BundleReturn::error(e.to_msg())),
+            Ok(validator) => (address, BundleReturn::success(validator))
+        }
+    }).collect();
+
+    to_json_binary(&StakingValidators {
+        bonded_denom,
+        all_validators,
+        validators
+    })
+}
+
+
+// ----------------------------------------------------------------- Stargate
+
+/// Queries `QueryRequest::Stargate` for each `(path, data)`, returning the raw protobuf response
+/// - Unlike [query_raw_key], System Errors are returned per item instead of failing the call
+#[cfg(feature = "stargate")]
+pub fn stargate_bundle_query(
+    deps: Deps,
+    queries: Vec<(String, Binary)>
+) -> StdResult<Binary> {
+
+    let mut res: Vec<(String, BundleReturn<Binary>)> = Vec::with_capacity(queries.len());
+
+    for (path, data) in queries.into_iter() {
+
+        let request: QueryRequest<Empty> = QueryRequest::Stargate {
+            path: path.clone(),
+            data
+        };
+
+        // The entire call will fail if serialization fails for -any- Query Request
+        let raw = cosmwasm_std::to_json_vec(&request).map_err(|serialize_err| {
+            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
+        })?;
+
+        match deps.querier.raw_query(&raw) {
+            SystemResult::Err(e) => res.push((path, BundleReturn::error(format!("System Err: {}", e)))),
+            SystemResult::Ok(ContractResult::Err(e)) => res.push((path, BundleReturn::error(e))),
+            SystemResult::Ok(ContractResult::Ok(val)) => res.push((path, BundleReturn::success(val)))
+        }
+    }
 
+    to_json_binary(&res)
 }
 
 
+// ----------------------------------------------------------------- IBC
+
+/// Queries this contract's `PortId`, optionally `ListChannels`, and `Channel` for each `(port, channel)`
+/// - `list_port_id` is the port passed to `ListChannels`, defaulting to this contract's port
+/// - Contracts that are not IBC enabled return errors instead of failing the call
+#[cfg(feature = "stargate")]
+pub fn ibc_channels_bundle_query(
+    deps: Deps,
+    list_channels: bool,
+    list_port_id: Option<String>,
+    channels: Vec<(String, String)>
+) -> StdResult<Binary> {
+
+    let port_id = match deps.querier.query::<PortIdResponse>(&IbcQuery::PortId {}.into()) {
+        Err(e) => BundleReturn::error(e.to_msg()),
+        Ok(res) => BundleReturn::success(res.port_id)
+    };
+
+    let list_channels = list_channels.then(|| {
+        match deps.querier.query::<ListChannelsResponse>(&IbcQuery::ListChannels { port_id: list_port_id }.into()) {
+            Err(e) => BundleReturn::error(e.to_msg()),
+            Ok(res) => BundleReturn::success(res.channels)
+        }
+    });
+
+    let channels = channels.into_iter().map(|(port_id, channel_id)| {
+        let request = IbcQuery::Channel {
+            channel_id: channel_id.clone(),
+            port_id: Some(port_id.clone())
+        };
+        match deps.querier.query::<ChannelResponse>(&request.into()) {
+            Err(e) => (port_id, channel_id, BundleReturn::error(e.to_msg())),
+            Ok(res) => (port_id, channel_id, BundleReturn::success(res.channel))
+        }
+    }).collect();
+
+    to_json_binary(&IbcChannels {
+        port_id,
+        list_channels,
+        channels
+    })
+}
+
 
 // ----------------------------------------------------------------- Generic
 
@@ -338,37 +2022,14 @@ pub fn generic_string_bundle_query_raw(
         // If key encoding fails for any token_id, the entire call fails
         let key = k.to_raw_map_key(namespace.as_str())?;
 
-        let request: QueryRequest<Empty> = WasmQuery::Raw {
-            contract_addr: contract.clone(),
-            key
-        }.into();
-    
-        // If query request serialization fails for any token_id, the entire call fails
-        let raw = cosmwasm_std::to_vec(&request).map_err(|serialize_err| {
-            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
-        })?;
-
-        // Entire call only fails if raw_query results in aSystem Error,
-        // If query results in Contract Error or Success, value is returned
-        let response = deps.querier.raw_query(&raw)
-            .into_result()
-            .map_err(|e| StdError::generic_err(format!("System Err: {}", e)))
-            .and_then(|val| match val {
-                ContractResult::Err(err) => Ok(QueryResRaw::error(err)),
-                ContractResult::Ok(val) => {
-                    // Handle null byte, which means key did not exist
-                    if val.len() == 0 {
-                        return Ok(QueryResRaw::error("Nonexistent key"));
-                    } else {
-                        return Ok(QueryResRaw::success(val.to_base64()));
-                    }
-                }
-            })?;
+        // Entire call only fails if request serialization fails for any token_id,
+        // or if raw_query results in a System Error
+        let response = to_query_res_raw(query_raw_key(deps, &contract, key)?);
 
         res.push((k.to_owned(), response));
     }
 
-    to_binary(&res)
+    to_json_binary(&res)
 }
 
 
@@ -403,36 +2064,13 @@ pub fn generic_uint_bundle_query_raw(
             IntType::U128 => (*k as u128).to_raw_map_key(namespace.as_str()),
         }?;
 
-        let request: QueryRequest<Empty> = WasmQuery::Raw {
-            contract_addr: contract.clone(),
-            key
-        }.into();
-    
-        // If query request serialization fails for any token_id, the entire call fails
-        let raw = cosmwasm_std::to_vec(&request).map_err(|serialize_err| {
-            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
-        })?;
-
-        // Entire call only fails if raw_query results in aSystem Error,
-        // If query results in Contract Error or Success, value is returned
-        let response = deps.querier.raw_query(&raw)
-            .into_result()
-            .map_err(|e| StdError::generic_err(format!("System Err: {}", e)))
-            .and_then(|val| match val {
-                ContractResult::Err(err) => Ok(QueryResRaw::error(err)),
-                ContractResult::Ok(val) => {
-                    // Handle null byte, which means key did not exist
-                    if val.len() == 0 {
-                        return Ok(QueryResRaw::error("Nonexistent key"));
-                    } else {
-                        return Ok(QueryResRaw::success(val.to_base64()));
-                    }
-                }
-            })?;
+        // Entire call only fails if request serialization fails for any token_id,
+        // or if raw_query results in a System Error
+        let response = to_query_res_raw(query_raw_key(deps, &contract, key)?);
 
         res.push((k.to_string(), response));
     }
 
-    to_binary(&res)
+    to_json_binary(&res)
 }
 